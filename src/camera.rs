@@ -1,8 +1,13 @@
 use crate::geometry::{TransformMatrix, Vector3};
 use crate::objects;
-use core::panic;
+use crate::shading;
 use std::vec;
 
+///aspect of a character cell pixel, used unless configured otherwise
+pub const DEFAULT_PIXEL_ASPECT: f64 = 0.5;
+///sine of the sharpest corner a triangle may have before it counts as a line
+const FLAT_TRIANGLE_EPSILON: f64 = 1e-9;
+
 #[derive(Copy, Clone)]
 pub struct CameraCorners {
     pub top_left: Vector3,
@@ -24,13 +29,15 @@ pub struct Camera {
     pub position: Vector3,
     pub origin: Vector3,
     pub corners: CameraCorners,
+    ///width divided by height of one output pixel, terminal cells are about twice as tall as wide
+    pub pixel_aspect: f64,
 }
 impl std::fmt::Display for Camera {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(
             f,
-            "Camera info:\n        Corners: {}\n\n        Origin: {}\n        Position: {}\n\n",
-            self.corners, self.origin, self.position
+            "Camera info:\n        Corners: {}\n\n        Origin: {}\n        Position: {}\n        Pixel aspect: {}\n\n",
+            self.corners, self.origin, self.position, self.pixel_aspect
         )
     }
 }
@@ -57,6 +64,7 @@ pub fn new_camera() -> Camera {
             y: 0.,
             z: 0.,
         },
+        pixel_aspect: DEFAULT_PIXEL_ASPECT,
     }
 }
 
@@ -97,66 +105,43 @@ fn initialize_camera_corners(position: Vector3, distance: f64) -> [Vector3; 4] {
     [top_left, top_right, bottom_left, bottom_right]
 }
 
-///iterates over world coords in camera
-
+///iterates over world coords in camera, sending `width` x `height` rays through the viewport;
+///`obj` is drawn as it is, `objects::update` it first if it moved
 pub fn raycasting(
-    corner1: Vector3,
-    corner2: Vector3,
-    corner3: Vector3,
-    corner4: Vector3,
     camera: Camera,
-    obj: objects::Object,
+    obj: &objects::Object,
+    light: shading::Light,
+    smoothing: shading::Shading,
+    width: usize,
+    height: usize,
 ) -> Vec<Vec<[u8; 3]>> {
+    let corner1 = camera.corners.top_left;
+    let corner2 = camera.corners.top_right;
+    let corner3 = camera.corners.bottom_left;
+    let corner4 = camera.corners.bottom_right;
+    let (scale_a, scale_b) = viewport_scale(camera, width, height);
     let mut map_2d: Vec<Vec<[u8; 3]>> = vec![];
 
     let mut ratio_a;
     let mut ratio_b;
-    for i in 0..100 {
+    for i in 0..height {
         let mut row: Vec<[u8; 3]> = vec![];
         //per new degree a new vec<>
-        for j in 0..100 {
-            ratio_a = j as f64 * 0.01;
-            ratio_b = i as f64 * 0.01;
+        for j in 0..width {
+            //pixel centers, stretched around the middle of the viewport
+            ratio_a = 0.5 + ((j as f64 + 0.5) / width as f64 - 0.5) * scale_a;
+            ratio_b = 0.5 + ((i as f64 + 0.5) / height as f64 - 0.5) * scale_b;
             //interpolating between the corners of the camera
-            let vec_t = corner1 * (1. - ratio_a) + corner2 * ratio_a;
-            let vec_b = corner3 * (1. - ratio_a) + corner4 * ratio_a;
-            let point_p = vec_t * (1. - ratio_b) + vec_b * ratio_b;
+            let vec_t = corner1.lerp(corner2, ratio_a);
+            let vec_b = corner3.lerp(corner4, ratio_a);
+            let point_p = vec_t.lerp(vec_b, ratio_b);
 
-            let vec_o = vector_ab(camera.position, point_p); //(point_p - camera.position;) //vec_o is the vector from camera position to point on viewport
+            let vec_o = point_p - camera.position; //vec_o is the vector from camera position to point on viewport
 
-            //for each polygon test vector vec_o get_intersection()
-            //two possibilities:    if i care about the face(future proof) get the closest face
-            //                      or just get the first intersection
-            let mut color: [u8; 3] = [0, 0, 0];
-            //checks if the line intersects an object
-            for i in 0..obj.polygons.len() {
-                let polygon = obj.polygons[i];
-                let point_1 = Vector3 {
-                    x: obj.transform_vertices[polygon[0]][0] + obj.origin.x,
-                    y: obj.transform_vertices[polygon[0]][1] + obj.origin.y,
-                    z: obj.transform_vertices[polygon[0]][2] + obj.origin.z,
-                };
-                let point_2 = Vector3 {
-                    x: obj.transform_vertices[polygon[1]][0] + obj.origin.x,
-                    y: obj.transform_vertices[polygon[1]][1] + obj.origin.y,
-                    z: obj.transform_vertices[polygon[1]][2] + obj.origin.z,
-                };
-                let point_3 = Vector3 {
-                    x: obj.transform_vertices[polygon[2]][0] + obj.origin.x,
-                    y: obj.transform_vertices[polygon[2]][1] + obj.origin.y,
-                    z: obj.transform_vertices[polygon[2]][2] + obj.origin.z,
-                };
-                let intersection =
-                    get_intersection(point_1, point_2, point_3, vec_o, camera.position); //stimmt das so???
-                let im_stupid_so_this_is_here = match intersection {
-                    Some(_point) => [255, 255, 255],
-                    None => [0, 0, 0], //hier continue
-                };
-
-                if im_stupid_so_this_is_here == [255, 255, 255] {
-                    color = im_stupid_so_this_is_here;
-                }
-            }
+            let color = match closest_hit(obj, camera.position, vec_o) {
+                Some(hit) => hit_color(obj, &hit, &light, smoothing, camera.position),
+                None => [0, 0, 0],
+            };
             row.push(color);
             //push filled vector to outer vector
         }
@@ -166,16 +151,134 @@ pub fn raycasting(
     map_2d
 }
 
-///returns the vector if the ray intersects the plane inside the triangle
+///how far the rays have to reach past the viewport edges so the image isn't distorted,
+///the viewport is always fully covered and only grows along one axis
+fn viewport_scale(camera: Camera, width: usize, height: usize) -> (f64, f64) {
+    let viewport_width = (camera.corners.top_right - camera.corners.top_left).length();
+    let viewport_height = (camera.corners.bottom_left - camera.corners.top_left).length();
+    let image_aspect = width as f64 * camera.pixel_aspect / height as f64;
+    let stretch = image_aspect / (viewport_width / viewport_height);
+    if stretch >= 1. {
+        (stretch, 1.)
+    } else {
+        (1., 1. / stretch)
+    }
+}
+
+///the polygon a ray runs into first
+#[derive(Copy, Clone, Debug)]
+pub struct Hit {
+    pub polygon: usize,
+    ///distance from the ray origin to `point`
+    pub distance: f64,
+    pub point: Vector3,
+    ///unit normal of the polygon, pointing to the side the ray came from
+    pub normal: Vector3,
+    ///barycentric coordinates of `point`, how much each corner of the polygon counts there
+    pub weights: [f64; 3],
+}
+impl std::fmt::Display for Hit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "Hit: polygon {} at distance {}\n        Point: {}\n        Normal: {}\n        Weights: {:?}",
+            self.polygon, self.distance, self.point, self.normal, self.weights
+        )
+    }
+}
+
+///blinn-phong color at the hit, smooth shading blends the corners' normals (phong) or their colors (gouraud)
+fn hit_color(
+    obj: &objects::Object,
+    hit: &Hit,
+    light: &shading::Light,
+    smoothing: shading::Shading,
+    camera_position: Vector3,
+) -> [u8; 3] {
+    let material = objects::polygon_material(obj, hit.polygon);
+    let color = objects::polygon_color(obj, hit.polygon);
+    let to_viewer = camera_position - hit.point;
+    let lit = |normal| shading::blinn_phong(light, &material, color, normal, to_viewer);
+    let normals = match objects::corner_normals(obj, hit.polygon) {
+        Some(normals) if smoothing != shading::Shading::Flat => normals,
+        _ => return lit(hit.normal),
+    };
+    //like the face normal, the corner normals have to face the ray
+    let normals = normals.map(|normal| {
+        if normal.dot(hit.normal) < 0. {
+            -normal
+        } else {
+            normal
+        }
+    });
+    let [w1, w2, w3] = hit.weights;
+    match smoothing {
+        shading::Shading::Gouraud => {
+            let corners = normals.map(lit);
+            [0, 1, 2].map(|channel| {
+                (w1 * corners[0][channel] as f64
+                    + w2 * corners[1][channel] as f64
+                    + w3 * corners[2][channel] as f64)
+                    .round()
+                    .clamp(0., 255.) as u8
+            })
+        }
+        _ => lit(normals[0] * w1 + normals[1] * w2 + normals[2] * w3),
+    }
+}
+
+///tests the ray against every polygon and keeps the one with the smallest distance
+pub fn closest_hit(obj: &objects::Object, ray_origin: Vector3, ray: Vector3) -> Option<Hit> {
+    let mut closest: Option<Hit> = None;
+    for i in 0..objects::mesh(obj).indices.len() {
+        let [point_1, point_2, point_3] = polygon_corners(obj, i);
+        if let Some((distance, point, plane_normal)) =
+            get_intersection(point_1, point_2, point_3, ray, ray_origin)
+        {
+            match closest {
+                Some(hit) if hit.distance <= distance => {}
+                _ => {
+                    //models don't always wind their corners the same way, so flip towards the ray origin
+                    let normal = if plane_normal.dot(ray) > 0. {
+                        -plane_normal
+                    } else {
+                        plane_normal
+                    };
+                    closest = Some(Hit {
+                        polygon: i,
+                        distance,
+                        point,
+                        normal: normal.normalize(),
+                        weights: barycentric(point_1, point_2, point_3, point),
+                    })
+                }
+            }
+        }
+    }
+    closest
+}
+
+///world coordinates of the polygon's corners
+fn polygon_corners(obj: &objects::Object, polygon_index: usize) -> [Vector3; 3] {
+    objects::mesh(obj).indices[polygon_index]
+        .map(|vertex_index| obj.transform_vertices[vertex_index])
+}
+
+///returns the distance along the ray, the point and the plane normal if the ray intersects the plane inside the triangle
 fn get_intersection(
     p1: Vector3, //p1-p3 are triangle corners
     p2: Vector3,
     p3: Vector3,
     vec_p: Vector3,                    //ray vector
     orig_p_and_camera_origin: Vector3, //point the ray "stands" on
-) -> Option<Vector3> {
-    let dir_vec_p = vector_to_unit_vector(vec_p);
-    let plane_normal = cross_product(vector_ab(p1, p2), vector_ab(p3, p1));
+) -> Option<(f64, Vector3, Vector3)> {
+    let dir_vec_p = vec_p.normalize();
+    let plane_normal = (p2 - p1).cross(p1 - p3);
+    //a triangle (almost) without area has no plane to hit, its normal would only be rounding noise;
+    //relative to the edges, so the size of the model doesn't matter
+    if plane_normal.length() <= FLAT_TRIANGLE_EPSILON * (p2 - p1).length() * (p1 - p3).length() {
+        return None;
+    }
 
     let normal = Vector3 {
         x: plane_normal.x,
@@ -183,10 +286,11 @@ fn get_intersection(
         z: plane_normal.z,
     };
 
-    let d: f64 = -1. * dot_product(normal, p3);
+    let d: f64 = -normal.dot(p3);
 
-    let t = -(dot_product(normal, orig_p_and_camera_origin) + d) / dot_product(normal, dir_vec_p);
-    if t < 0. {
+    let t = -(normal.dot(orig_p_and_camera_origin) + d) / normal.dot(dir_vec_p);
+    //a ray parallel to the plane gives an infinite or NaN t
+    if t < 0. || !t.is_finite() {
         return None;
     }
 
@@ -194,12 +298,11 @@ fn get_intersection(
     let p = orig_p_and_camera_origin + dir_vec_p * t;
     if point_inside_triangle(plane_normal, p1, p2, p3, p) {
         //order matters! 123 and 321 show very different results! probably because the normal is flipped
-        return Some(p);
+        return Some((t, p, plane_normal));
     }
     None
 }
 fn point_inside_triangle(
-    //the issue probably arrises here...
     plane_normal: Vector3,
     triangle_p1: Vector3,
     triangle_p2: Vector3,
@@ -207,68 +310,22 @@ fn point_inside_triangle(
     point_on_plane: Vector3,
 ) -> bool {
     let _ = plane_normal;
-    let v_12 = vector_ab(triangle_p2, triangle_p1);
-    let v_23 = vector_ab(triangle_p3, triangle_p2);
-    let v_31 = vector_ab(triangle_p1, triangle_p3);
-
-    let v_1p = vector_ab(point_on_plane, triangle_p1);
-    let v_2p = vector_ab(point_on_plane, triangle_p2);
-    let v_3p = vector_ab(point_on_plane, triangle_p3);
-
-    let v12_cross_v1p = cross_product(v_12, v_1p);
-    let v23_cross_v2p = cross_product(v_23, v_2p);
-    let v31_cross_v3p = cross_product(v_31, v_3p);
-
-    let barycentric_a = dot_product(v12_cross_v1p, v23_cross_v2p);
-    let barycentric_b = dot_product(v12_cross_v1p, v31_cross_v3p);
-    let barycentric_c = dot_product(v31_cross_v3p, v23_cross_v2p);
-    //if they have the same sign => point in triangle
-
-    barycentric_a > 0. && barycentric_b > 0. && barycentric_c > 0.
-}
-fn vector_ab(a: Vector3, b: Vector3) -> Vector3 {
-    Vector3 {
-        x: b.x - a.x,
-        y: b.y - a.y,
-        z: b.z - a.z,
-    }
-}
-fn cross_product(v_1: Vector3, v_2: Vector3) -> Vector3 {
-    Vector3 {
-        x: (v_1.y * v_2.z - v_2.y * v_1.z),
-        y: -1. * (v_1.x * v_2.z - v_2.x * v_1.z),
-        z: (v_1.x * v_2.y - v_2.x * v_1.y),
-    }
-}
-
-fn dot_product(v_1: Vector3, v_2: Vector3) -> f64 {
-    v_1.x * v_2.x + v_1.y * v_2.y + v_1.z * v_2.z
-}
-
-fn vector_to_unit_vector(vec: Vector3) -> Vector3 {
-    let len = length_of_vector(vec);
-    let vec = Vector3 {
-        x: vec.x / len,
-        y: vec.y / len,
-        z: vec.z / len,
-    };
-    if vec.x.is_nan() || vec.y.is_nan() || vec.z.is_nan() {
-        panic!();
-    }
-    vec
+    barycentric(triangle_p1, triangle_p2, triangle_p3, point_on_plane)
+        .iter()
+        .all(|weight| *weight > 0.)
 }
-fn length_of_vector(vec: Vector3) -> f64 {
-    let aux_vec = f64::sqrt(vec.x * vec.x + vec.y * vec.y);
-    if aux_vec.is_nan() {
-        panic!()
-    }
-    let res = f64::sqrt(aux_vec * aux_vec + vec.z * vec.z);
-    if res.is_nan() {
-        panic!()
-    }
-    res
+///how much each corner counts at a point on the triangle's plane, the weights add up to 1;
+///outside the triangle at least one of them is negative
+fn barycentric(p1: Vector3, p2: Vector3, p3: Vector3, point: Vector3) -> [f64; 3] {
+    let normal = (p2 - p1).cross(p3 - p1);
+    let area = normal.dot(normal);
+    //every weight is the area of the triangle the point forms with the other two corners
+    [
+        (p2 - point).cross(p3 - point).dot(normal) / area,
+        (p3 - point).cross(p1 - point).dot(normal) / area,
+        (p1 - point).cross(p2 - point).dot(normal) / area,
+    ]
 }
-
 fn _turn_90_degrees(vec: Vector3) -> Vector3 {
     let transform_matrix = TransformMatrix {
         row_1: Vector3 {
@@ -302,12 +359,14 @@ pub fn _transform_camera(camera: &Camera) -> Camera {
         corners,
         position,
         origin,
+        pixel_aspect: camera.pixel_aspect,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::geometry::Mesh;
     #[test]
     fn test_point_inside_triangle_1() {
         let plane_normal = Vector3 {
@@ -335,7 +394,7 @@ mod tests {
             y: 0.,
             z: 100.,
         };
-        assert_eq!(point_inside_triangle(plane_normal, p1, p2, p3, p), true);
+        assert!(point_inside_triangle(plane_normal, p1, p2, p3, p));
     }
     #[test]
     fn test_point_inside_triangle_2() {
@@ -364,7 +423,19 @@ mod tests {
             y: -9.,
             z: 100.,
         };
-        assert_eq!(point_inside_triangle(plane_normal, p1, p2, p3, p), true);
+        assert!(point_inside_triangle(plane_normal, p1, p2, p3, p));
+    }
+    #[test]
+    fn test_barycentric() {
+        let corner = |x, y| Vector3 { x, y, z: 0. };
+        let (p1, p2, p3) = (corner(0., 0.), corner(2., 0.), corner(0., 2.));
+        let weights = barycentric(p1, p2, p3, corner(0.5, 0.5));
+        for (weight, expected) in weights.iter().zip([0.5, 0.25, 0.25]) {
+            assert!((weight - expected).abs() < 1e-12);
+        }
+        //the corners weigh 1 at themselves, outside one weight turns negative
+        assert!(barycentric(p1, p2, p3, p2) == [0., 1., 0.]);
+        assert!(barycentric(p1, p2, p3, corner(2., 2.))[0] < 0.);
     }
     #[test]
     fn test_point_inside_triangle_3() {
@@ -393,7 +464,7 @@ mod tests {
             y: 0.,
             z: 99.,
         };
-        assert_eq!(point_inside_triangle(plane_normal, p1, p2, p3, p), false);
+        assert!(!point_inside_triangle(plane_normal, p1, p2, p3, p));
     }
     #[test]
     fn test_get_intersection_1() {
@@ -423,7 +494,7 @@ mod tests {
             z: 0.,
         };
         let res: Vector3 = match get_intersection(p1, p2, p3, vec_p, orig_p_and_camera_origin) {
-            Some(some) => some,
+            Some((_, some, _)) => some,
             None => Vector3 {
                 x: 0.,
                 y: 0.,
@@ -440,8 +511,56 @@ mod tests {
         )
     }
     #[test]
+    fn test_get_intersection_flat_triangle() {
+        let ray_origin = Vector3 {
+            x: 0.5,
+            y: 0.,
+            z: -10.,
+        };
+        let ray = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 1.,
+        };
+        let corner = |x, y| Vector3 { x, y, z: 0. };
+        //a line, a sliver whose third corner is a rounding error off the line, and a point
+        assert!(get_intersection(
+            corner(0., 0.),
+            corner(1., 0.),
+            corner(2., 0.),
+            ray,
+            ray_origin
+        )
+        .is_none());
+        assert!(get_intersection(
+            corner(-1., -1e-12),
+            corner(1., 0.),
+            corner(3., 3e-12),
+            ray,
+            ray_origin
+        )
+        .is_none());
+        assert!(get_intersection(
+            corner(1., 1.),
+            corner(1., 1.),
+            corner(1., 1.),
+            ray,
+            ray_origin
+        )
+        .is_none());
+        //a thin but real triangle is still hit
+        assert!(get_intersection(
+            corner(0., -1e-3),
+            corner(1., 2e-3),
+            corner(1., -1e-3),
+            ray,
+            ray_origin
+        )
+        .is_some());
+    }
+    #[test]
     fn test_get_intersection_2() {
-        let _vertices = vec![
+        let _vertices = [
             [1000., -1200., 0.],
             [-1000., -1200., 0.],
             [0., 400., 0.],
@@ -478,7 +597,7 @@ mod tests {
                 z: 0.,
             },
         ) {
-            Some(some) => some,
+            Some((_, some, _)) => some,
             None => Vector3 {
                 x: 0.,
                 y: 0.,
@@ -495,14 +614,145 @@ mod tests {
         )
     }
 
+    ///two parallel triangles facing the camera, `near_first` decides their order in the polygon list
+    fn two_layer_obj(near_first: bool) -> objects::Object {
+        let triangle = |z: f64| {
+            [
+                Vector3 { x: 10., y: -10., z },
+                Vector3 {
+                    x: -10.,
+                    y: -10.,
+                    z,
+                },
+                Vector3 { x: 0., y: 10., z },
+            ]
+        };
+        let (first, second) = if near_first {
+            (triangle(-20.), triangle(20.))
+        } else {
+            (triangle(20.), triangle(-20.))
+        };
+        let mut vertices = first.to_vec();
+        vertices.extend_from_slice(&second);
+        objects::new_object(Mesh::new(vertices, vec![[0, 1, 2], [3, 4, 5]]))
+    }
+    #[test]
+    fn test_closest_hit_keeps_nearest_polygon() {
+        let ray_origin = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        };
+        let ray = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 1.,
+        };
+        //the object origin moves both triangles to z = 80 and z = 120
+        for (near_first, expected_polygon) in [(true, 0), (false, 1)] {
+            let hit = closest_hit(&two_layer_obj(near_first), ray_origin, ray).unwrap();
+            assert_eq!(hit.polygon, expected_polygon);
+            assert!((hit.distance - 80.).abs() < 1e-9);
+            assert!((hit.point.z - 80.).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn test_closest_hit_miss() {
+        let ray_origin = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        };
+        let away = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: -1.,
+        };
+        let beside = Vector3 {
+            x: 1.,
+            y: 0.,
+            z: 0.1,
+        };
+        assert!(closest_hit(&two_layer_obj(true), ray_origin, away).is_none());
+        assert!(closest_hit(&two_layer_obj(true), ray_origin, beside).is_none());
+    }
+
+    #[test]
+    fn test_raycasting_keeps_proportions() {
+        //a square facing the camera has to come out square for every pixel aspect
+        let half = 10.;
+        let vertices = vec![
+            Vector3 {
+                x: -half,
+                y: -half,
+                z: 0.,
+            },
+            Vector3 {
+                x: half,
+                y: -half,
+                z: 0.,
+            },
+            Vector3 {
+                x: half,
+                y: half,
+                z: 0.,
+            },
+            Vector3 {
+                x: -half,
+                y: half,
+                z: 0.,
+            },
+        ];
+        let square = objects::new_object(Mesh::new(vertices, vec![[0, 1, 2], [0, 2, 3]]));
+        let light = crate::shading::Light {
+            direction: Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 1.,
+            },
+            intensity: 1.,
+        };
+        for (pixel_aspect, width, height) in
+            [(1., 40, 40), (0.5, 80, 40), (0.5, 40, 40), (1., 30, 60)]
+        {
+            let mut camera = new_camera();
+            camera.pixel_aspect = pixel_aspect;
+            let frame = raycasting(
+                camera,
+                &square,
+                light,
+                shading::Shading::Flat,
+                width,
+                height,
+            );
+            let hit_rows = frame
+                .iter()
+                .filter(|row| row.iter().any(|pixel| *pixel != [0, 0, 0]))
+                .count();
+            let hit_columns = (0..width)
+                .filter(|x| frame.iter().any(|row| row[*x] != [0, 0, 0]))
+                .count();
+            let shown_width = hit_columns as f64 * pixel_aspect;
+            assert!(
+                (shown_width - hit_rows as f64).abs() <= 1.5,
+                "{} x {} pixels for aspect {} at {}x{}",
+                hit_columns,
+                hit_rows,
+                pixel_aspect,
+                width,
+                height
+            );
+        }
+    }
     #[test]
     fn test_vector_to_unit_vector_1() {
         assert_eq!(
-            vector_to_unit_vector(Vector3 {
+            Vector3 {
                 x: 16.,
                 y: 0.,
                 z: 0.
-            }),
+            }
+            .normalize(),
             Vector3 {
                 x: 1.,
                 y: 0.,
@@ -510,11 +760,12 @@ mod tests {
             }
         );
         assert_eq!(
-            vector_to_unit_vector(Vector3 {
+            Vector3 {
                 x: 0.,
                 y: 340.3,
                 z: 0.
-            }),
+            }
+            .normalize(),
             Vector3 {
                 x: 0.,
                 y: 1.,
@@ -522,11 +773,12 @@ mod tests {
             }
         );
         assert_eq!(
-            vector_to_unit_vector(Vector3 {
+            Vector3 {
                 x: 0.,
                 y: 0.,
                 z: -23.23
-            }),
+            }
+            .normalize(),
             Vector3 {
                 x: 0.,
                 y: 0.,
@@ -538,77 +790,82 @@ mod tests {
     #[test]
     fn test_length_of_vector_1() {
         assert_eq!(
-            length_of_vector(Vector3 {
+            Vector3 {
                 x: 1.,
                 y: 0.,
                 z: 0.
-            }),
+            }
+            .length(),
             1.
         );
         assert_eq!(
-            length_of_vector(Vector3 {
+            Vector3 {
                 x: 0.,
                 y: 1.,
                 z: 0.
-            }),
+            }
+            .length(),
             1.
         );
         assert_eq!(
-            length_of_vector(Vector3 {
+            Vector3 {
                 x: 0.,
                 y: 0.,
                 z: 1.
-            }),
+            }
+            .length(),
             1.
         );
         assert_eq!(
-            length_of_vector(Vector3 {
+            Vector3 {
                 x: -1.,
                 y: 0.,
                 z: 0.
-            }),
+            }
+            .length(),
             1.
         );
         assert_eq!(
-            length_of_vector(Vector3 {
+            Vector3 {
                 x: 0.,
                 y: 0.,
                 z: 0.
-            }),
+            }
+            .length(),
             0.
         );
         assert_eq!(
-            length_of_vector(Vector3 {
+            Vector3 {
                 x: 4.,
                 y: 3.,
                 z: 0.
-            }),
+            }
+            .length(),
             5.
         );
         assert_eq!(
-            length_of_vector(Vector3 {
+            Vector3 {
                 x: 4.,
                 y: 0.,
                 z: -3.
-            }),
+            }
+            .length(),
             5.
         );
     }
     #[test]
     fn test_cross_product_1() {
         assert_eq!(
-            cross_product(
-                Vector3 {
-                    x: 1.,
-                    y: 2.,
-                    z: 3.
-                },
-                Vector3 {
-                    x: 4.,
-                    y: 5.,
-                    z: 6.
-                }
-            ),
+            Vector3 {
+                x: 1.,
+                y: 2.,
+                z: 3.
+            }
+            .cross(Vector3 {
+                x: 4.,
+                y: 5.,
+                z: 6.
+            }),
             Vector3 {
                 x: -3.,
                 y: 6.,
@@ -620,66 +877,57 @@ mod tests {
     #[test]
     fn test_dot_product_1() {
         assert_eq!(
-            dot_product(
-                Vector3 {
-                    x: 4.,
-                    y: 0.,
-                    z: 0.
-                },
-                Vector3 {
-                    x: 4.,
-                    y: 0.,
-                    z: 0.
-                }
-            ),
+            Vector3 {
+                x: 4.,
+                y: 0.,
+                z: 0.
+            }
+            .dot(Vector3 {
+                x: 4.,
+                y: 0.,
+                z: 0.
+            }),
             16.
         );
         assert_eq!(
-            dot_product(
-                Vector3 {
-                    x: 4.,
-                    y: 0.,
-                    z: 0.
-                },
-                Vector3 {
-                    x: -4.,
-                    y: 0.,
-                    z: 0.
-                }
-            ),
+            Vector3 {
+                x: 4.,
+                y: 0.,
+                z: 0.
+            }
+            .dot(Vector3 {
+                x: -4.,
+                y: 0.,
+                z: 0.
+            }),
             -16.
         );
         assert_eq!(
-            dot_product(
-                Vector3 {
-                    x: 4.,
-                    y: 0.,
-                    z: 0.
-                },
-                Vector3 {
-                    x: 0.,
-                    y: 0.,
-                    z: 1.
-                }
-            ),
+            Vector3 {
+                x: 4.,
+                y: 0.,
+                z: 0.
+            }
+            .dot(Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 1.
+            }),
             0.
         );
     }
     #[test]
     fn test_vector_ab_1() {
         assert_eq!(
-            vector_ab(
-                Vector3 {
-                    x: 4.,
-                    y: 0.,
-                    z: -3.
-                },
-                Vector3 {
-                    x: 4.,
-                    y: 0.,
-                    z: -3.
-                }
-            ),
+            Vector3 {
+                x: 4.,
+                y: 0.,
+                z: -3.
+            } - Vector3 {
+                x: 4.,
+                y: 0.,
+                z: -3.
+            },
             Vector3 {
                 x: 0.,
                 y: 0.,
@@ -687,18 +935,15 @@ mod tests {
             }
         );
         assert_eq!(
-            vector_ab(
-                Vector3 {
-                    x: -4.,
-                    y: 4.,
-                    z: 25.
-                },
-                Vector3 {
-                    x: 4.,
-                    y: -2.,
-                    z: -3.
-                }
-            ),
+            Vector3 {
+                x: 4.,
+                y: -2.,
+                z: -3.
+            } - Vector3 {
+                x: -4.,
+                y: 4.,
+                z: 25.
+            },
             Vector3 {
                 x: 8.,
                 y: -6.,
@@ -706,18 +951,15 @@ mod tests {
             }
         );
         assert_eq!(
-            vector_ab(
-                Vector3 {
-                    x: 42.,
-                    y: 34.,
-                    z: -33.
-                },
-                Vector3 {
-                    x: 4.,
-                    y: 0.,
-                    z: -3.
-                }
-            ),
+            Vector3 {
+                x: 4.,
+                y: 0.,
+                z: -3.
+            } - Vector3 {
+                x: 42.,
+                y: 34.,
+                z: -33.
+            },
             Vector3 {
                 x: -38.,
                 y: -34.,
@@ -725,18 +967,15 @@ mod tests {
             }
         );
         assert_eq!(
-            vector_ab(
-                Vector3 {
-                    x: 4.,
-                    y: 22.,
-                    z: -32.
-                },
-                Vector3 {
-                    x: 4.,
-                    y: 12.,
-                    z: -3.
-                }
-            ),
+            Vector3 {
+                x: 4.,
+                y: 12.,
+                z: -3.
+            } - Vector3 {
+                x: 4.,
+                y: 22.,
+                z: -32.
+            },
             Vector3 {
                 x: 0.,
                 y: -10.,
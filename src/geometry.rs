@@ -11,6 +11,171 @@ impl std::fmt::Display for TransformMatrix {
         write!(f, "{}\n{}\n{}\n", self.row_1, self.row_2, self.row_3)
     }
 }
+///the order in which `TransformMatrix::from_euler` applies its three rotations
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum EulerOrder {
+    ///x first, then y, then z
+    Xyz,
+    Xzy,
+    Yxz,
+    Yzx,
+    Zxy,
+    Zyx,
+}
+impl TransformMatrix {
+    pub fn identity() -> TransformMatrix {
+        TransformMatrix::scale(1., 1., 1.)
+    }
+    pub fn scale(x: f64, y: f64, z: f64) -> TransformMatrix {
+        TransformMatrix {
+            row_1: Vector3 { x, y: 0., z: 0. },
+            row_2: Vector3 { x: 0., y, z: 0. },
+            row_3: Vector3 { x: 0., y: 0., z },
+        }
+    }
+    ///rotation around the x axis, `angle` in radians, y turns towards z
+    pub fn rotation_x(angle: f64) -> TransformMatrix {
+        let (sin, cos) = angle.sin_cos();
+        TransformMatrix {
+            row_1: Vector3 {
+                x: 1.,
+                y: 0.,
+                z: 0.,
+            },
+            row_2: Vector3 {
+                x: 0.,
+                y: cos,
+                z: -sin,
+            },
+            row_3: Vector3 {
+                x: 0.,
+                y: sin,
+                z: cos,
+            },
+        }
+    }
+    ///rotation around the y axis, `angle` in radians, z turns towards x
+    pub fn rotation_y(angle: f64) -> TransformMatrix {
+        let (sin, cos) = angle.sin_cos();
+        TransformMatrix {
+            row_1: Vector3 {
+                x: cos,
+                y: 0.,
+                z: sin,
+            },
+            row_2: Vector3 {
+                x: 0.,
+                y: 1.,
+                z: 0.,
+            },
+            row_3: Vector3 {
+                x: -sin,
+                y: 0.,
+                z: cos,
+            },
+        }
+    }
+    ///rotation around the z axis, `angle` in radians, x turns towards y
+    pub fn rotation_z(angle: f64) -> TransformMatrix {
+        let (sin, cos) = angle.sin_cos();
+        TransformMatrix {
+            row_1: Vector3 {
+                x: cos,
+                y: -sin,
+                z: 0.,
+            },
+            row_2: Vector3 {
+                x: sin,
+                y: cos,
+                z: 0.,
+            },
+            row_3: Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 1.,
+            },
+        }
+    }
+    ///rotation by `angle` radians around `axis`, `None` if the axis has no length
+    pub fn from_axis_angle(axis: Vector3, angle: f64) -> Option<TransformMatrix> {
+        Quaternion::from_axis_angle(axis, angle).map(TransformMatrix::from)
+    }
+    ///rotations around the x, y and z axes (radians), applied one after another in `order`
+    pub fn from_euler(x: f64, y: f64, z: f64, order: EulerOrder) -> TransformMatrix {
+        let (rx, ry, rz) = (
+            TransformMatrix::rotation_x(x),
+            TransformMatrix::rotation_y(y),
+            TransformMatrix::rotation_z(z),
+        );
+        //the rotation applied first stands rightmost
+        match order {
+            EulerOrder::Xyz => rz * ry * rx,
+            EulerOrder::Xzy => ry * rz * rx,
+            EulerOrder::Yxz => rz * rx * ry,
+            EulerOrder::Yzx => rx * rz * ry,
+            EulerOrder::Zxy => ry * rx * rz,
+            EulerOrder::Zyx => rx * ry * rz,
+        }
+    }
+    ///rows become columns
+    pub fn transpose(&self) -> TransformMatrix {
+        TransformMatrix {
+            row_1: Vector3 {
+                x: self.row_1.x,
+                y: self.row_2.x,
+                z: self.row_3.x,
+            },
+            row_2: Vector3 {
+                x: self.row_1.y,
+                y: self.row_2.y,
+                z: self.row_3.y,
+            },
+            row_3: Vector3 {
+                x: self.row_1.z,
+                y: self.row_2.z,
+                z: self.row_3.z,
+            },
+        }
+    }
+    pub fn determinant(&self) -> f64 {
+        self.row_1.dot(self.row_2.cross(self.row_3))
+    }
+    ///`None` if the matrix flattens space, e.g. a scale by 0
+    pub fn inverse(&self) -> Option<TransformMatrix> {
+        let determinant = self.determinant();
+        if determinant == 0. || !determinant.is_finite() {
+            return None;
+        }
+        //the columns of the inverse are the cross products of the rows
+        let adjugate = TransformMatrix {
+            row_1: self.row_2.cross(self.row_3),
+            row_2: self.row_3.cross(self.row_1),
+            row_3: self.row_1.cross(self.row_2),
+        }
+        .transpose();
+        Some(TransformMatrix {
+            row_1: adjugate.row_1 / determinant,
+            row_2: adjugate.row_2 / determinant,
+            row_3: adjugate.row_3 / determinant,
+        })
+    }
+    ///the inverse transpose, keeps normals perpendicular to their surface under non-uniform scale
+    pub fn normal_matrix(&self) -> Option<TransformMatrix> {
+        self.inverse().map(|inverse| inverse.transpose())
+    }
+    ///an orthonormal basis from the rows (gram-schmidt, the first row keeps its direction), removes the drift of many multiplications
+    pub fn orthonormalize(&self) -> TransformMatrix {
+        let row_1 = self.row_1.normalize();
+        let row_2 = (self.row_2 - row_1 * self.row_2.dot(row_1)).normalize();
+        //the third row follows from the other two, this also keeps the handedness
+        let row_3 = row_1.cross(row_2);
+        TransformMatrix {
+            row_1,
+            row_2,
+            row_3,
+        }
+    }
+}
 impl ops::Mul<Vector3> for TransformMatrix {
     type Output = Vector3;
     fn mul(self, vec: Vector3) -> Self::Output {
@@ -21,6 +186,289 @@ impl ops::Mul<Vector3> for TransformMatrix {
         }
     }
 }
+///affine transform in homogeneous coordinates, the last row stays 0 0 0 1
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Matrix4 {
+    pub rows: [[f64; 4]; 4],
+}
+impl Matrix4 {
+    pub fn identity() -> Matrix4 {
+        Matrix4::from(TransformMatrix::identity())
+    }
+    pub fn translation(offset: Vector3) -> Matrix4 {
+        let mut matrix = Matrix4::identity();
+        for axis in 0..3 {
+            matrix.rows[axis][3] = offset[axis];
+        }
+        matrix
+    }
+    pub fn scale(x: f64, y: f64, z: f64) -> Matrix4 {
+        Matrix4::from(TransformMatrix::scale(x, y, z))
+    }
+    ///where the origin ends up
+    pub fn translation_part(&self) -> Vector3 {
+        Vector3 {
+            x: self.rows[0][3],
+            y: self.rows[1][3],
+            z: self.rows[2][3],
+        }
+    }
+    ///transforms a position, translation included
+    pub fn transform_point(&self, point: Vector3) -> Vector3 {
+        let row = |row: [f64; 4]| row[0] * point.x + row[1] * point.y + row[2] * point.z + row[3];
+        Vector3 {
+            x: row(self.rows[0]),
+            y: row(self.rows[1]),
+            z: row(self.rows[2]),
+        }
+    }
+}
+impl From<TransformMatrix> for Matrix4 {
+    fn from(matrix: TransformMatrix) -> Matrix4 {
+        let mut rows = [[0.; 4]; 4];
+        for (row_index, row) in rows.iter_mut().enumerate().take(3) {
+            for col_index in 0..3 {
+                row[col_index] = matrix[row_index][col_index];
+            }
+        }
+        rows[3][3] = 1.;
+        Matrix4 { rows }
+    }
+}
+impl ops::Mul for Matrix4 {
+    type Output = Matrix4;
+    ///`a * b` applies `b` first
+    fn mul(self, matrix_2: Matrix4) -> Self::Output {
+        let mut rows = [[0.; 4]; 4];
+        for (row_index, row) in rows.iter_mut().enumerate() {
+            for (col_index, value) in row.iter_mut().enumerate() {
+                *value = (0..4)
+                    .map(|i| self.rows[row_index][i] * matrix_2.rows[i][col_index])
+                    .sum();
+            }
+        }
+        Matrix4 { rows }
+    }
+}
+impl std::fmt::Display for Matrix4 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows {
+            writeln!(f, "{} {} {} {}", row[0], row[1], row[2], row[3])?;
+        }
+        Ok(())
+    }
+}
+///a rotation as unit quaternion, w is the real part
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Quaternion {
+    pub w: f64,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+impl Quaternion {
+    pub fn identity() -> Quaternion {
+        Quaternion {
+            w: 1.,
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        }
+    }
+    ///rotation by `angle` radians around `axis`, `None` if the axis has no length
+    pub fn from_axis_angle(axis: Vector3, angle: f64) -> Option<Quaternion> {
+        let length = axis.length();
+        if length == 0. || !length.is_finite() {
+            return None;
+        }
+        let (sin, cos) = (angle / 2.).sin_cos();
+        let axis = axis / length * sin;
+        Some(Quaternion {
+            w: cos,
+            x: axis.x,
+            y: axis.y,
+            z: axis.z,
+        })
+    }
+    fn dot(self, other: Quaternion) -> f64 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+    fn scaled(self, factor: f64) -> Quaternion {
+        Quaternion {
+            w: self.w * factor,
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+    #[allow(dead_code)]
+    fn plus(self, other: Quaternion) -> Quaternion {
+        Quaternion {
+            w: self.w + other.w,
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+    ///scales back to length 1, rounding errors of many multiplications can't pile up
+    pub fn normalize(self) -> Quaternion {
+        let length = self.dot(self).sqrt();
+        if length == 0. || !length.is_finite() {
+            return Quaternion::identity();
+        }
+        self.scaled(1. / length)
+    }
+    ///spherical interpolation, `t` = 0 gives `self` and 1 gives `other`, always along the shorter way
+    #[allow(dead_code)]
+    pub fn slerp(self, other: Quaternion, t: f64) -> Quaternion {
+        let mut other = other;
+        let mut cos = self.dot(other);
+        //q and -q are the same rotation, the one closer to self takes the short way
+        if cos < 0. {
+            other = other.scaled(-1.);
+            cos = -cos;
+        }
+        let angle = cos.min(1.).acos();
+        let sin = angle.sin();
+        if sin < 1e-6 {
+            //nearly the same rotation, a straight line is just as good and doesn't divide by 0
+            return self.scaled(1. - t).plus(other.scaled(t)).normalize();
+        }
+        self.scaled(((1. - t) * angle).sin() / sin)
+            .plus(other.scaled((t * angle).sin() / sin))
+            .normalize()
+    }
+}
+impl ops::Mul for Quaternion {
+    type Output = Quaternion;
+    ///`a * b` rotates by `b` first
+    fn mul(self, q: Quaternion) -> Self::Output {
+        Quaternion {
+            w: self.w * q.w - self.x * q.x - self.y * q.y - self.z * q.z,
+            x: self.w * q.x + self.x * q.w + self.y * q.z - self.z * q.y,
+            y: self.w * q.y - self.x * q.z + self.y * q.w + self.z * q.x,
+            z: self.w * q.z + self.x * q.y - self.y * q.x + self.z * q.w,
+        }
+    }
+}
+impl From<Quaternion> for TransformMatrix {
+    fn from(q: Quaternion) -> TransformMatrix {
+        let q = q.normalize();
+        let (w, x, y, z) = (q.w, q.x, q.y, q.z);
+        TransformMatrix {
+            row_1: Vector3 {
+                x: 1. - 2. * (y * y + z * z),
+                y: 2. * (x * y - w * z),
+                z: 2. * (x * z + w * y),
+            },
+            row_2: Vector3 {
+                x: 2. * (x * y + w * z),
+                y: 1. - 2. * (x * x + z * z),
+                z: 2. * (y * z - w * x),
+            },
+            row_3: Vector3 {
+                x: 2. * (x * z - w * y),
+                y: 2. * (y * z + w * x),
+                z: 1. - 2. * (x * x + y * y),
+            },
+        }
+    }
+}
+impl From<TransformMatrix> for Quaternion {
+    ///the matrix is orthonormalized first, so a drifted rotation still gives a unit quaternion
+    fn from(matrix: TransformMatrix) -> Quaternion {
+        let m = matrix.orthonormalize();
+        let trace = m.row_1.x + m.row_2.y + m.row_3.z;
+        //divides by the largest of w, x, y and z to stay accurate
+        let q = if trace > 0. {
+            let s = (trace + 1.).sqrt() * 2.;
+            Quaternion {
+                w: s / 4.,
+                x: (m.row_3.y - m.row_2.z) / s,
+                y: (m.row_1.z - m.row_3.x) / s,
+                z: (m.row_2.x - m.row_1.y) / s,
+            }
+        } else if m.row_1.x > m.row_2.y && m.row_1.x > m.row_3.z {
+            let s = (1. + m.row_1.x - m.row_2.y - m.row_3.z).sqrt() * 2.;
+            Quaternion {
+                w: (m.row_3.y - m.row_2.z) / s,
+                x: s / 4.,
+                y: (m.row_1.y + m.row_2.x) / s,
+                z: (m.row_1.z + m.row_3.x) / s,
+            }
+        } else if m.row_2.y > m.row_3.z {
+            let s = (1. + m.row_2.y - m.row_1.x - m.row_3.z).sqrt() * 2.;
+            Quaternion {
+                w: (m.row_1.z - m.row_3.x) / s,
+                x: (m.row_1.y + m.row_2.x) / s,
+                y: s / 4.,
+                z: (m.row_2.z + m.row_3.y) / s,
+            }
+        } else {
+            let s = (1. + m.row_3.z - m.row_1.x - m.row_2.y).sqrt() * 2.;
+            Quaternion {
+                w: (m.row_2.x - m.row_1.y) / s,
+                x: (m.row_1.z + m.row_3.x) / s,
+                y: (m.row_2.z + m.row_3.y) / s,
+                z: s / 4.,
+            }
+        };
+        q.normalize()
+    }
+}
+impl std::fmt::Display for Quaternion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "w: {}, x: {}, y: {}, z: {}",
+            self.w, self.x, self.y, self.z
+        )
+    }
+}
+///scale, rotation and translation of a model, kept apart so none of them drifts into the others
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Transform {
+    pub translation: Vector3,
+    pub rotation: Quaternion,
+    pub scale: Vector3,
+    ///the point of the rest pose that scale and rotation turn around, it ends up at `translation`
+    pub pivot: Vector3,
+}
+impl Transform {
+    pub fn identity() -> Transform {
+        let zero = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        };
+        Transform {
+            translation: zero,
+            rotation: Quaternion::identity(),
+            scale: Vector3 {
+                x: 1.,
+                y: 1.,
+                z: 1.,
+            },
+            pivot: zero,
+        }
+    }
+    ///moves the pivot to the origin, scales, rotates and then translates
+    pub fn matrix(&self) -> Matrix4 {
+        Matrix4::translation(self.translation)
+            * Matrix4::from(TransformMatrix::from(self.rotation))
+            * Matrix4::scale(self.scale.x, self.scale.y, self.scale.z)
+            * Matrix4::translation(-self.pivot)
+    }
+}
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "Translation: {}\n        Rotation: {}\n        Scale: {}\n        Pivot: {}",
+            self.translation, self.rotation, self.scale, self.pivot
+        )
+    }
+}
 struct _Plane {
     a: f64,
     b: f64,
@@ -54,7 +502,6 @@ impl IndexMut<usize> for Vector3 {
     }
 }
 
-type Point = Vector3;
 impl Index<usize> for Vector3 {
     type Output = f64;
     fn index(&self, index: usize) -> &Self::Output {
@@ -86,44 +533,162 @@ impl ops::Add for Vector3 {
         }
     }
 }
+impl ops::AddAssign for Vector3 {
+    fn add_assign(&mut self, vec: Vector3) {
+        *self = *self + vec;
+    }
+}
+impl ops::Sub for Vector3 {
+    type Output = Vector3;
+    fn sub(self, vec: Vector3) -> Self::Output {
+        Vector3 {
+            x: self.x - vec.x,
+            y: self.y - vec.y,
+            z: self.z - vec.z,
+        }
+    }
+}
+impl ops::Neg for Vector3 {
+    type Output = Vector3;
+    fn neg(self) -> Self::Output {
+        self * -1.
+    }
+}
+impl ops::Div<f64> for Vector3 {
+    type Output = Vector3;
+    fn div(self, scalar: f64) -> Self::Output {
+        Vector3 {
+            x: self.x / scalar,
+            y: self.y / scalar,
+            z: self.z / scalar,
+        }
+    }
+}
+///component-wise product
+impl ops::Mul<Vector3> for Vector3 {
+    type Output = Vector3;
+    fn mul(self, vec: Vector3) -> Self::Output {
+        Vector3 {
+            x: self.x * vec.x,
+            y: self.y * vec.y,
+            z: self.z * vec.z,
+        }
+    }
+}
+impl Vector3 {
+    pub fn dot(self, vec: Vector3) -> f64 {
+        self.x * vec.x + self.y * vec.y + self.z * vec.z
+    }
+    ///perpendicular to both, right handed
+    pub fn cross(self, vec: Vector3) -> Vector3 {
+        Vector3 {
+            x: self.y * vec.z - vec.y * self.z,
+            y: self.z * vec.x - vec.z * self.x,
+            z: self.x * vec.y - vec.x * self.y,
+        }
+    }
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+    ///same direction with length 1, a zero vector stays zero
+    pub fn normalize(self) -> Vector3 {
+        let length = self.length();
+        if length == 0. {
+            return self;
+        }
+        self / length
+    }
+    ///`self` at `t` = 0, `vec` at `t` = 1
+    pub fn lerp(self, vec: Vector3, t: f64) -> Vector3 {
+        self * (1. - t) + vec * t
+    }
+    ///mirrors the vector on the plane with the unit normal `normal`
+    #[allow(dead_code)]
+    pub fn reflect(self, normal: Vector3) -> Vector3 {
+        self - normal * (2. * self.dot(normal))
+    }
+    ///true if no component differs by more than `eps`
+    pub fn approx_eq(self, vec: Vector3, eps: f64) -> bool {
+        (self.x - vec.x).abs() <= eps
+            && (self.y - vec.y).abs() <= eps
+            && (self.z - vec.z).abs() <= eps
+    }
+}
 impl std::fmt::Display for Vector3 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(f, "x: {}, y: {}, z: {}", self.x, self.y, self.z)
     }
 }
-const _UNIT_MATRIX: TransformMatrix = TransformMatrix {
-    row_1: Vector3 {
-        x: 1.,
-        y: 0.,
-        z: 0.,
-    },
-    row_2: Vector3 {
-        x: 0.,
-        y: 1.,
-        z: 0.,
-    },
-    row_3: Vector3 {
-        x: 0.,
-        y: 0.,
-        z: 1.,
-    },
-};
-
-#[derive(Clone)]
-pub struct Object {
-    pub origin: Point,
-    pub vertices: Vec<[f64; 3]>,
-    pub _transform_vertices: Vec<[f64; 3]>,
-    pub polygons: Vec<[usize; 3]>,
-    pub _transform_matrix: TransformMatrix,
-    pub _tranlation_vector: Vector3,
-}
-impl std::fmt::Display for Object {
+///triangles over shared vertices, the one model type loaders produce and the renderer draws
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mesh {
+    ///vertex positions in object space
+    pub positions: Vec<Vector3>,
+    ///three indices into `positions` per triangle
+    pub indices: Vec<[usize; 3]>,
+    ///one normal per vertex, `None` for vertices the file gave none
+    pub normals: Option<Vec<Option<Vector3>>>,
+    ///one texture coordinate per vertex
+    pub uvs: Option<Vec<[f64; 2]>>,
+    ///one color per vertex
+    pub colors: Option<Vec<[u8; 3]>>,
+    ///one normal per triangle, as stored in the model file
+    pub face_normals: Option<Vec<Vector3>>,
+    ///one material per triangle, 0 is the default material
+    pub material_ids: Option<Vec<usize>>,
+    ///name of every material id but the default one, id `i` is named `material_names[i - 1]`
+    pub material_names: Vec<String>,
+    ///material library files the model refers to, relative to the model file
+    pub material_libraries: Vec<String>,
+}
+impl Mesh {
+    pub fn new(positions: Vec<Vector3>, indices: Vec<[usize; 3]>) -> Mesh {
+        Mesh {
+            positions,
+            indices,
+            normals: None,
+            uvs: None,
+            colors: None,
+            face_normals: None,
+            material_ids: None,
+            material_names: vec![],
+            material_libraries: vec![],
+        }
+    }
+    ///gives every vertex without a normal the area weighted average of its triangles' normals,
+    ///large triangles count more than slivers; this assumes the triangles are wound consistently
+    pub fn fill_normals(&mut self) {
+        let mut sums = vec![
+            Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 0.,
+            };
+            self.positions.len()
+        ];
+        for [a, b, c] in &self.indices {
+            //the cross product is as long as twice the triangle's area
+            let weighted = (self.positions[*b] - self.positions[*a])
+                .cross(self.positions[*c] - self.positions[*a]);
+            for corner in [a, b, c] {
+                sums[*corner] += weighted;
+            }
+        }
+        let normals = self.normals.get_or_insert_with(|| vec![None; sums.len()]);
+        for (normal, sum) in normals.iter_mut().zip(sums) {
+            //vertices outside of any triangle, or only in flat ones, stay without
+            if normal.is_none() && sum.length() > 0. {
+                *normal = Some(sum.normalize());
+            }
+        }
+    }
+}
+impl std::fmt::Display for Mesh {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(
             f,
-            "Object:\n        Origin: {}\n        Vertices: {:?}\n        Polygon list: {:?}\n\n",
-            self.origin, self.vertices, self.polygons
+            "Mesh:\n        Positions: {:?}\n        Triangles: {:?}\n        Normals: {:?}\n        UVs: {:?}\n        Colors: {:?}\n        Face normals: {:?}\n        Materials: {:?} {:?}\n        Material libraries: {:?}\n\n",
+            self.positions, self.indices, self.normals, self.uvs, self.colors, self.face_normals, self.material_ids, self.material_names, self.material_libraries
         )
     }
 }
@@ -175,14 +740,25 @@ impl Index<usize> for TransformMatrix {
         }
     }
 }
+///same product as for `Vector3`, the array only changes the packaging
 impl ops::Mul<[f64; 3]> for TransformMatrix {
     type Output = [f64; 3];
     fn mul(self, vec: [f64; 3]) -> Self::Output {
-        [
-            self.row_1.x * vec[0] + self.row_1.y * vec[0] + self.row_1.z * vec[0],
-            self.row_2.x * vec[1] + self.row_2.y * vec[1] + self.row_2.z * vec[1],
-            self.row_3.x * vec[2] + self.row_3.y * vec[2] + self.row_3.z * vec[2],
-        ]
+        (self * Vector3::from(vec)).into()
+    }
+}
+impl From<[f64; 3]> for Vector3 {
+    fn from(vec: [f64; 3]) -> Vector3 {
+        Vector3 {
+            x: vec[0],
+            y: vec[1],
+            z: vec[2],
+        }
+    }
+}
+impl From<Vector3> for [f64; 3] {
+    fn from(vec: Vector3) -> [f64; 3] {
+        [vec.x, vec.y, vec.z]
     }
 }
 
@@ -242,11 +818,11 @@ mod tests {
                 z: 4.,
             },
         };
-        assert_eq!(m1 * m2 == m_res, true);
+        assert!(m1 * m2 == m_res);
     }
     #[test]
     fn test_add_for_vector3_1() {
-        assert_eq!(
+        assert!(
             Vector3 {
                 x: 0.,
                 y: 0.,
@@ -259,13 +835,12 @@ mod tests {
                 x: 0.,
                 y: 0.,
                 z: 0.
-            },
-            true
+            }
         );
     }
     #[test]
     fn test_add_for_vector3_2() {
-        assert_eq!(
+        assert!(
             Vector3 {
                 x: 4.,
                 y: -4.,
@@ -278,13 +853,12 @@ mod tests {
                 x: 11.,
                 y: -2.,
                 z: 6.
-            },
-            true
+            }
         );
     }
     #[test]
     fn test_mul_for_f64_vector3() {
-        assert_eq!(
+        assert!(
             Vector3 {
                 x: 7.,
                 y: 2.,
@@ -294,13 +868,12 @@ mod tests {
                     x: 14.,
                     y: 4.,
                     z: 6.
-                },
-            true
+                }
         );
     }
     #[test]
     fn test_mul_for_vector3_transformmatrix() {
-        assert_eq!(
+        assert!(
             TransformMatrix {
                 row_1: Vector3 {
                     x: 6.,
@@ -325,8 +898,519 @@ mod tests {
                 x: 24.,
                 y: -9.,
                 z: -23.
+            }
+        );
+    }
+    fn approx(a: &TransformMatrix, b: &TransformMatrix) -> bool {
+        (0..3).all(|row| a[row].approx_eq(b[row], 1e-9))
+    }
+    fn is_rotation(matrix: &TransformMatrix) -> bool {
+        approx(
+            &(matrix.transpose() * matrix.clone()),
+            &TransformMatrix::identity(),
+        )
+    }
+    #[test]
+    fn test_rotation_axes() {
+        let quarter = std::f64::consts::FRAC_PI_2;
+        let x = Vector3 {
+            x: 1.,
+            y: 0.,
+            z: 0.,
+        };
+        let y = Vector3 {
+            x: 0.,
+            y: 1.,
+            z: 0.,
+        };
+        let z = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 1.,
+        };
+        let close = |a: Vector3, b: Vector3| a.approx_eq(b, 1e-12);
+        assert!(close(TransformMatrix::rotation_x(quarter) * y, z));
+        assert!(close(TransformMatrix::rotation_y(quarter) * z, x));
+        assert!(close(TransformMatrix::rotation_z(quarter) * x, y));
+        assert!(TransformMatrix::identity() * x == x);
+        assert!(
+            TransformMatrix::scale(2., 3., 4.)
+                * Vector3 {
+                    x: 1.,
+                    y: 1.,
+                    z: 1.
+                }
+                == Vector3 {
+                    x: 2.,
+                    y: 3.,
+                    z: 4.
+                }
+        );
+    }
+    #[test]
+    fn test_from_axis_angle() {
+        let angle = 0.7;
+        let axis = |x, y, z| Vector3 { x, y, z };
+        assert!(approx(
+            &TransformMatrix::from_axis_angle(axis(3., 0., 0.), angle).unwrap(),
+            &TransformMatrix::rotation_x(angle)
+        ));
+        assert!(approx(
+            &TransformMatrix::from_axis_angle(axis(0., 1., 0.), angle).unwrap(),
+            &TransformMatrix::rotation_y(angle)
+        ));
+        assert!(approx(
+            &TransformMatrix::from_axis_angle(axis(0., 0., 0.5), angle).unwrap(),
+            &TransformMatrix::rotation_z(angle)
+        ));
+        let tilted = TransformMatrix::from_axis_angle(axis(1., 2., -3.), angle).unwrap();
+        assert!(is_rotation(&tilted));
+        //the axis itself doesn't move
+        let moved = tilted * axis(1., 2., -3.);
+        assert!(moved.approx_eq(axis(1., 2., -3.), 1e-12));
+        assert!(TransformMatrix::from_axis_angle(axis(0., 0., 0.), angle).is_none());
+    }
+    #[test]
+    fn test_from_euler() {
+        let (x, y, z) = (0.3, -1.1, 2.);
+        let (rx, ry, rz) = (
+            TransformMatrix::rotation_x(x),
+            TransformMatrix::rotation_y(y),
+            TransformMatrix::rotation_z(z),
+        );
+        assert!(approx(
+            &TransformMatrix::from_euler(x, y, z, EulerOrder::Xyz),
+            &(rz.clone() * ry.clone() * rx.clone())
+        ));
+        assert!(approx(
+            &TransformMatrix::from_euler(x, y, z, EulerOrder::Zyx),
+            &(rx * ry * rz)
+        ));
+        assert!(!approx(
+            &TransformMatrix::from_euler(x, y, z, EulerOrder::Yxz),
+            &TransformMatrix::from_euler(x, y, z, EulerOrder::Zxy)
+        ));
+        assert!(is_rotation(&TransformMatrix::from_euler(
+            x,
+            y,
+            z,
+            EulerOrder::Xzy
+        )));
+    }
+    #[test]
+    fn test_orthonormalize() {
+        let step = TransformMatrix::rotation_y(1f64.to_radians());
+        let mut drifted = TransformMatrix::identity();
+        for _ in 0..360 {
+            drifted = step.clone() * drifted;
+            drifted.row_1 = drifted.row_1 * 1.001;
+            drifted.row_2.x += 0.001;
+        }
+        assert!(!is_rotation(&drifted));
+        let fixed = drifted.orthonormalize();
+        assert!(is_rotation(&fixed));
+        //the determinant stays 1, no mirroring
+        assert!((fixed.determinant() - 1.).abs() < 1e-9);
+        let rotation = TransformMatrix::rotation_z(0.4);
+        assert!(approx(&rotation.orthonormalize(), &rotation));
+    }
+    #[test]
+    fn test_matrix4_transform_point() {
+        let point = Vector3 {
+            x: 1.,
+            y: 2.,
+            z: 3.,
+        };
+        let offset = Vector3 {
+            x: 10.,
+            y: 0.,
+            z: -5.,
+        };
+        assert!(Matrix4::identity().transform_point(point) == point);
+        assert!(
+            Matrix4::translation(offset).transform_point(point)
+                == Vector3 {
+                    x: 11.,
+                    y: 2.,
+                    z: -2.
+                }
+        );
+        assert!(
+            Matrix4::scale(2., 2., -1.).transform_point(point)
+                == Vector3 {
+                    x: 2.,
+                    y: 4.,
+                    z: -3.
+                }
+        );
+        let rotation = TransformMatrix::rotation_z(0.5);
+        let rotated = Matrix4::from(rotation.clone()).transform_point(point);
+        let expected = rotation * point;
+        assert!(rotated.approx_eq(expected, 1e-12));
+        assert!(Matrix4::translation(offset).translation_part() == offset);
+    }
+    #[test]
+    fn test_matrix4_composition() {
+        let point = Vector3 {
+            x: 1.,
+            y: 0.,
+            z: 0.,
+        };
+        let offset = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 100.,
+        };
+        let quarter = Matrix4::from(TransformMatrix::rotation_z(std::f64::consts::FRAC_PI_2));
+        //rotate first, then move
+        let moved = (Matrix4::translation(offset) * quarter).transform_point(point);
+        assert!(moved.approx_eq(vec(0., 1., 100.), 1e-12));
+        //move first, then rotate around the world origin, the offset lies on the z axis so it stays
+        let orbited = (quarter * Matrix4::translation(offset)).transform_point(point);
+        assert!(orbited.approx_eq(vec(0., 1., 100.), 1e-12));
+        assert!((Matrix4::translation(offset) * Matrix4::scale(2., 2., 2.))
+            .translation_part()
+            .approx_eq(offset, 1e-12));
+        assert!(Matrix4::identity() * quarter == quarter);
+    }
+    #[test]
+    fn test_quaternion_matrix_round_trip() {
+        let axis = Vector3 {
+            x: 1.,
+            y: -2.,
+            z: 0.5,
+        };
+        for angle in [0., 0.3, 2., 3.1, -1.7] {
+            let q = Quaternion::from_axis_angle(axis, angle).unwrap();
+            let matrix = TransformMatrix::from(q);
+            assert!(is_rotation(&matrix));
+            //q and -q are the same rotation
+            let back = Quaternion::from(matrix);
+            assert!((back.dot(q).abs() - 1.).abs() < 1e-9, "{}", angle);
+        }
+        //every branch of the matrix conversion, the half turns have a trace of -1
+        for matrix in [
+            TransformMatrix::rotation_x(3.),
+            TransformMatrix::rotation_y(3.),
+            TransformMatrix::rotation_z(3.),
+            TransformMatrix::from_euler(0.4, -2.9, 1.3, EulerOrder::Yxz),
+        ] {
+            assert!(approx(
+                &TransformMatrix::from(Quaternion::from(matrix.clone())),
+                &matrix
+            ));
+        }
+    }
+    #[test]
+    fn test_quaternion_product() {
+        let (a, b) = (
+            TransformMatrix::rotation_x(0.8),
+            TransformMatrix::rotation_z(-1.2),
+        );
+        let product = Quaternion::from(a.clone()) * Quaternion::from(b.clone());
+        assert!(approx(&TransformMatrix::from(product), &(a * b)));
+        assert!(Quaternion::identity() * product == product);
+        let long = Quaternion {
+            w: 2.,
+            x: 0.,
+            y: 0.,
+            z: 0.,
+        };
+        assert!(long.normalize() == Quaternion::identity());
+    }
+    #[test]
+    fn test_slerp() {
+        let axis = Vector3 {
+            x: 0.,
+            y: 1.,
+            z: 0.,
+        };
+        let start = Quaternion::identity();
+        let end = Quaternion::from_axis_angle(axis, 2.).unwrap();
+        let halfway = Quaternion::from_axis_angle(axis, 1.).unwrap();
+        assert!((start.slerp(end, 0.5).dot(halfway) - 1.).abs() < 1e-12);
+        assert!((start.slerp(end, 0.).dot(start) - 1.).abs() < 1e-12);
+        assert!((start.slerp(end, 1.).dot(end) - 1.).abs() < 1e-12);
+        //past the end keeps turning at the same speed
+        let further = Quaternion::from_axis_angle(axis, 3.).unwrap();
+        assert!((start.slerp(end, 1.5).dot(further) - 1.).abs() < 1e-12);
+        //the flipped sign of the same rotation still takes the short way
+        let flipped = end.scaled(-1.);
+        assert!((start.slerp(flipped, 0.5).dot(halfway).abs() - 1.).abs() < 1e-12);
+        //tiny steps don't divide by 0
+        let tiny = Quaternion::from_axis_angle(axis, 1e-9).unwrap();
+        assert!(start.slerp(tiny, 0.5).w.is_finite());
+    }
+    #[test]
+    fn test_transform_matrix() {
+        let point = Vector3 {
+            x: 1.,
+            y: 0.,
+            z: 0.,
+        };
+        assert!(Transform::identity().matrix() == Matrix4::identity());
+        let transform = Transform {
+            translation: Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 100.,
             },
-            true
+            rotation: Quaternion::from(TransformMatrix::rotation_z(std::f64::consts::FRAC_PI_2)),
+            scale: Vector3 {
+                x: 3.,
+                y: 1.,
+                z: 1.,
+            },
+            pivot: Vector3 {
+                x: -1.,
+                y: 0.,
+                z: 0.,
+            },
+        };
+        //the pivot lands on the translation
+        let pivot = transform.matrix().transform_point(transform.pivot);
+        assert!(pivot.approx_eq(transform.translation, 1e-12));
+        //2 away from the pivot along x, scaled to 6, turned onto y
+        let moved = transform.matrix().transform_point(point);
+        assert!(moved.approx_eq(vec(0., 6., 100.), 1e-12));
+    }
+    fn vec(x: f64, y: f64, z: f64) -> Vector3 {
+        Vector3 { x, y, z }
+    }
+    #[test]
+    fn test_vector3_operators() {
+        let a = vec(4., -2., 25.);
+        let b = vec(4., 0., -3.);
+        assert!(a - a == vec(0., 0., 0.));
+        assert!(b - a == vec(0., 2., -28.));
+        assert!(vec(4., 12., -3.) - vec(4., 22., -32.) == vec(0., -10., 29.));
+        assert!(-a == vec(-4., 2., -25.));
+        assert!(a / 2. == vec(2., -1., 12.5));
+        assert!(a * b == vec(16., -0., -75.));
+        let mut sum = a;
+        sum += b;
+        assert!(sum == a + b);
+    }
+    #[test]
+    fn test_dot_and_cross() {
+        assert_eq!(vec(4., 0., 0.).dot(vec(4., 0., 0.)), 16.);
+        assert_eq!(vec(4., 0., 0.).dot(vec(-4., 0., 0.)), -16.);
+        assert_eq!(vec(4., 0., 0.).dot(vec(0., 0., 1.)), 0.);
+        assert!(vec(1., 2., 3.).cross(vec(4., 5., 6.)) == vec(-3., 6., -3.));
+        assert!(vec(1., 0., 0.).cross(vec(0., 1., 0.)) == vec(0., 0., 1.));
+    }
+    #[test]
+    fn test_length_and_normalize() {
+        for (vector, length) in [
+            (vec(1., 0., 0.), 1.),
+            (vec(0., 1., 0.), 1.),
+            (vec(0., 0., 1.), 1.),
+            (vec(-1., 0., 0.), 1.),
+            (vec(0., 0., 0.), 0.),
+            (vec(4., 3., 0.), 5.),
+            (vec(4., 0., -3.), 5.),
+        ] {
+            assert_eq!(vector.length(), length);
+        }
+        let eps = 1e-12;
+        assert!(vec(16., 0., 0.).normalize().approx_eq(vec(1., 0., 0.), eps));
+        assert!(vec(0., 340.3, 0.)
+            .normalize()
+            .approx_eq(vec(0., 1., 0.), eps));
+        assert!(vec(0., 0., -23.23)
+            .normalize()
+            .approx_eq(vec(0., 0., -1.), eps));
+        assert!((vec(3., -7., 0.5).normalize().length() - 1.).abs() < eps);
+        assert!(vec(0., 0., 0.).normalize() == vec(0., 0., 0.));
+    }
+    #[test]
+    fn test_lerp_and_reflect() {
+        let a = vec(0., 10., -4.);
+        let b = vec(10., 0., 4.);
+        assert!(a.lerp(b, 0.).approx_eq(a, 1e-12));
+        assert!(a.lerp(b, 1.).approx_eq(b, 1e-12));
+        assert!(a.lerp(b, 0.5).approx_eq(vec(5., 5., 0.), 1e-12));
+        //a ray hitting the floor bounces back up
+        let floor = vec(0., 1., 0.);
+        assert!(vec(1., -1., 0.)
+            .reflect(floor)
+            .approx_eq(vec(1., 1., 0.), 1e-12));
+        assert!(vec(1., 0., 0.)
+            .reflect(floor)
+            .approx_eq(vec(1., 0., 0.), 1e-12));
+    }
+    #[test]
+    fn test_approx_eq() {
+        let a = vec(1., 2., 3.);
+        assert!(a.approx_eq(vec(1.05, 1.95, 3.), 0.1));
+        assert!(!a.approx_eq(vec(1., 2., 3.2), 0.1));
+        assert!(a.approx_eq(a, 0.));
+        //0.1 + 0.2 is not exactly 0.3
+        assert!(vec(0.1 + 0.2, 0., 0.).approx_eq(vec(0.3, 0., 0.), 1e-12));
+        assert!(vec(0.1 + 0.2, 0., 0.) != vec(0.3, 0., 0.));
+    }
+    fn sample_matrix() -> TransformMatrix {
+        TransformMatrix {
+            row_1: vec(2., -1., 0.5),
+            row_2: vec(0., 3., 1.),
+            row_3: vec(4., 0., -2.),
+        }
+    }
+    #[test]
+    fn test_transpose_and_determinant() {
+        let m = sample_matrix();
+        assert!(m.transpose().row_1 == vec(2., 0., 4.));
+        assert!(m.transpose().transpose() == m);
+        assert_eq!(m.determinant(), -12. - 4. - 6.);
+        assert_eq!(m.transpose().determinant(), m.determinant());
+        assert_eq!(TransformMatrix::scale(2., 3., 4.).determinant(), 24.);
+        assert!((TransformMatrix::rotation_x(1.3).determinant() - 1.).abs() < 1e-12);
+        //swapping two rows mirrors
+        assert_eq!(
+            TransformMatrix {
+                row_1: vec(0., 1., 0.),
+                row_2: vec(1., 0., 0.),
+                row_3: vec(0., 0., 1.),
+            }
+            .determinant(),
+            -1.
+        );
+    }
+    #[test]
+    fn test_inverse() {
+        let m = sample_matrix();
+        let inverse = m.inverse().unwrap();
+        assert!(approx(
+            &(m.clone() * inverse.clone()),
+            &TransformMatrix::identity()
+        ));
+        assert!(approx(&(inverse * m), &TransformMatrix::identity()));
+        let rotation = TransformMatrix::from_euler(0.3, 1., -2., EulerOrder::Zxy);
+        assert!(approx(&rotation.inverse().unwrap(), &rotation.transpose()));
+        assert!(TransformMatrix::scale(1., 0., 1.).inverse().is_none());
+        let flat = TransformMatrix {
+            row_1: vec(1., 2., 3.),
+            row_2: vec(2., 4., 6.),
+            row_3: vec(0., 1., 0.),
+        };
+        assert!(flat.inverse().is_none());
+    }
+    #[test]
+    fn test_normal_matrix() {
+        //a 45 degree slope squashed to half its height gets flatter, its normal has to tilt up
+        let squash = TransformMatrix::scale(1., 0.5, 1.);
+        let along = vec(1., 1., 0.);
+        let normal = vec(-1., 1., 0.);
+        let squashed_along = squash.clone() * along;
+        let naive = squash.clone() * normal;
+        assert!(naive.dot(squashed_along).abs() > 0.1);
+        let correct = squash.normal_matrix().unwrap() * normal;
+        assert!(correct.dot(squashed_along).abs() < 1e-12);
+        //for rotations it is the rotation itself
+        let rotation = TransformMatrix::rotation_y(0.8);
+        assert!(approx(&rotation.normal_matrix().unwrap(), &rotation));
+        assert!(TransformMatrix::scale(0., 1., 1.).normal_matrix().is_none());
+    }
+    ///xorshift, enough randomness for property tests without pulling in a crate
+    struct Random(u64);
+    impl Random {
+        ///uniform between -100 and 100
+        fn next(&mut self) -> f64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 >> 11) as f64 / (1u64 << 53) as f64 * 200. - 100.
+        }
+        fn vector(&mut self) -> Vector3 {
+            vec(self.next(), self.next(), self.next())
+        }
+        fn matrix(&mut self) -> TransformMatrix {
+            TransformMatrix {
+                row_1: self.vector(),
+                row_2: self.vector(),
+                row_3: self.vector(),
+            }
+        }
+    }
+    #[test]
+    fn test_array_conversions() {
+        let array = [1.5, -2., 3.25];
+        assert!(Vector3::from(array) == vec(1.5, -2., 3.25));
+        assert_eq!(<[f64; 3]>::from(Vector3::from(array)), array);
+    }
+    #[test]
+    fn test_mul_for_array_transformmatrix() {
+        //the old version multiplied every row by a single component, only diagonal matrices survived that
+        let m = sample_matrix();
+        assert_eq!(m.clone() * [1., 0., 0.], [2., 0., 4.]);
+        assert_eq!(m * [1., 2., 3.], [1.5, 9., -2.]);
+    }
+    #[test]
+    fn test_array_and_vector_paths_agree() {
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..1000 {
+            let m = random.matrix();
+            let v = random.vector();
+            let array: [f64; 3] = v.into();
+            let by_array = m.clone() * array;
+            let by_vector = m.clone() * v;
+            assert_eq!(by_array, <[f64; 3]>::from(by_vector));
+            //and both are the textbook sum over the columns
+            for (row, value) in by_array.iter().enumerate() {
+                let expected: f64 = (0..3).map(|col| m[row][col] * array[col]).sum();
+                assert!((value - expected).abs() <= 1e-9 * expected.abs().max(1.));
+            }
+        }
+    }
+    #[test]
+    fn test_matrix_product_applies_right_factor_first() {
+        let mut random = Random(42);
+        for _ in 0..200 {
+            let (a, b) = (random.matrix(), random.matrix());
+            let v = random.vector();
+            let at_once: [f64; 3] = (a.clone() * b.clone()) * <[f64; 3]>::from(v);
+            let one_by_one = a * (b * v);
+            let scale = one_by_one.length().max(1.);
+            assert!(Vector3::from(at_once).approx_eq(one_by_one, 1e-9 * scale));
+        }
+    }
+    #[test]
+    fn test_fill_normals_weights_by_area() {
+        //a big triangle facing +z and a small one facing -x share the edge 0-1
+        let mut mesh = Mesh::new(
+            vec![
+                vec(0., 0., 0.),
+                vec(0., 1., 0.),
+                vec(-3., 0., 0.),
+                vec(0., 0., 1.),
+                vec(5., 5., 5.),
+            ],
+            vec![[0, 1, 2], [0, 3, 1]],
+        );
+        //the file stored a zero normal for vertex 3, that is kept like any other
+        mesh.normals = Some(vec![
+            None,
+            None,
+            Some(vec(0., 1., 0.)),
+            Some(vec(0., 0., 0.)),
+            None,
+        ]);
+        mesh.fill_normals();
+        let normals = mesh.normals.unwrap();
+        //twice the areas are 3 and 1
+        let shared = vec(-1., 0., 3.).normalize();
+        assert!(normals[0].unwrap().approx_eq(shared, 1e-12));
+        assert!(normals[1].unwrap().approx_eq(shared, 1e-12));
+        //normals the mesh had are kept, vertices without triangles get none
+        assert!(normals[2] == Some(vec(0., 1., 0.)));
+        assert!(normals[3] == Some(vec(0., 0., 0.)));
+        assert!(normals[4].is_none());
+        //a mesh without any normals gets them all
+        let mut bare = Mesh::new(
+            vec![vec(0., 0., 0.), vec(1., 0., 0.), vec(0., 1., 0.)],
+            vec![[0, 1, 2]],
         );
+        bare.fill_normals();
+        assert!(bare.normals.unwrap() == vec![Some(vec(0., 0., 1.)); 3]);
     }
 }
@@ -0,0 +1,139 @@
+use crate::geometry::Mesh;
+use crate::shading::Material;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+pub mod mtl;
+pub mod obj;
+pub mod ply;
+pub mod stl;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    ///a line that could not be understood, `line` starts at 1
+    Parse {
+        line: usize,
+        message: String,
+    },
+    ///a face references a vertex that does not exist (yet)
+    IndexOutOfRange {
+        line: usize,
+        index: i64,
+        vertex_count: usize,
+    },
+    ///a binary file whose structure does not add up
+    Malformed(String),
+    UnsupportedFormat(String),
+}
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            LoadError::Io(err) => write!(f, "could not read file: {}", err),
+            LoadError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            LoadError::IndexOutOfRange {
+                line,
+                index,
+                vertex_count,
+            } => write!(
+                f,
+                "line {}: vertex index {} is out of range ({} vertices defined)",
+                line, index, vertex_count
+            ),
+            LoadError::Malformed(message) => write!(f, "malformed model: {}", message),
+            LoadError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported model format: '{}'", extension)
+            }
+        }
+    }
+}
+impl std::error::Error for LoadError {}
+impl From<std::io::Error> for LoadError {
+    fn from(err: std::io::Error) -> Self {
+        LoadError::Io(err)
+    }
+}
+
+///loads a model file, the format is picked by the file extension
+pub fn load(path: &Path) -> Result<Mesh, LoadError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match extension.as_str() {
+        "obj" => obj::parse_obj(BufReader::new(File::open(path)?)),
+        "stl" => stl::parse_stl(&read_bytes(path)?),
+        "ply" => ply::parse_ply(&read_bytes(path)?),
+        _ => Err(LoadError::UnsupportedFormat(extension)),
+    }
+}
+
+///reads the material libraries `mesh` refers to, they are looked up next to the model file at `path`;
+///gives the material id of every material found, names no library defines are left out
+pub fn load_materials(
+    path: &Path,
+    mesh: &Mesh,
+    base: Material,
+) -> Result<Vec<(usize, Material)>, LoadError> {
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut defined: Vec<(String, Material)> = vec![];
+    for library in &mesh.material_libraries {
+        let file = File::open(directory.join(library))?;
+        defined.extend(mtl::parse_mtl(BufReader::new(file), base)?);
+    }
+    Ok(mesh
+        .material_names
+        .iter()
+        .enumerate()
+        .filter_map(|(index, name)| {
+            //a later definition of the same name wins
+            let (_, material) = defined.iter().rev().find(|(known, _)| known == name)?;
+            Some((index + 1, *material))
+        })
+        .collect())
+}
+
+fn read_bytes(path: &Path) -> Result<Vec<u8>, LoadError> {
+    let mut bytes = vec![];
+    File::open(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shading::new_material;
+
+    #[test]
+    fn test_load_materials_next_to_the_model() {
+        let directory = std::env::temp_dir().join(format!("load_materials_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            directory.join("colors.mtl"),
+            "newmtl red\nKd 1 0 0\nnewmtl green\nKd 0 1 0\n",
+        )
+        .unwrap();
+        let model = directory.join("model.obj");
+        std::fs::write(
+            &model,
+            "mtllib colors.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl green\nf 1 2 3\n\
+             usemtl unknown\nf 1 2 3\nusemtl red\nf 1 2 3\n",
+        )
+        .unwrap();
+        let mesh = load(&model).unwrap();
+        let materials = load_materials(&model, &mesh, new_material());
+        std::fs::remove_dir_all(&directory).unwrap();
+        let materials = materials.unwrap();
+        assert_eq!(materials.len(), 2);
+        assert_eq!(materials[0].0, 1);
+        assert_eq!(materials[0].1.diffuse, [0, 255, 0]);
+        assert_eq!(materials[1].0, 3);
+        assert_eq!(materials[1].1.diffuse, [255, 0, 0]);
+        assert!(matches!(
+            load_materials(&model, &mesh, new_material()),
+            Err(LoadError::Io(_))
+        ));
+    }
+}
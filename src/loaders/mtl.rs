@@ -0,0 +1,119 @@
+use super::obj::parse_numbers;
+use super::LoadError;
+use crate::shading::Material;
+use std::io::BufRead;
+
+///reads the `newmtl`, `Kd`, `Ks`, `Ns` and `Ka` records of a wavefront mtl file,
+///whatever a material leaves out is taken from `base`
+pub fn parse_mtl<R: BufRead>(
+    reader: R,
+    base: Material,
+) -> Result<Vec<(String, Material)>, LoadError> {
+    let mut materials: Vec<(String, Material)> = vec![];
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        let content = match line.find('#') {
+            Some(position) => &line[..position],
+            None => &line,
+        };
+        let mut tokens = content.split_whitespace();
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+        if keyword == "newmtl" {
+            let name = tokens.next().ok_or_else(|| LoadError::Parse {
+                line: line_number,
+                message: "newmtl needs a material name".to_owned(),
+            })?;
+            materials.push((name.to_owned(), base));
+            continue;
+        }
+        if !["Kd", "Ks", "Ns", "Ka"].contains(&keyword) {
+            //textures, transparency, illumination models, ... are not drawn
+            continue;
+        }
+        let material = match materials.last_mut() {
+            Some((_, material)) => material,
+            None => {
+                return Err(LoadError::Parse {
+                    line: line_number,
+                    message: format!("{} before the first newmtl", keyword),
+                })
+            }
+        };
+        let values = parse_numbers(tokens, line_number)?;
+        match (keyword, values.as_slice()) {
+            ("Ns", [exponent]) => {
+                //an exponent of 0 would light the whole surface as a highlight
+                material.shininess = exponent.max(1.);
+            }
+            ("Kd" | "Ks" | "Ka", [r] | [r, _, _]) => {
+                //"Kd r" is gray, the same as "Kd r r r"
+                let color = match values.as_slice() {
+                    [_, g, b] => [*r, *g, *b],
+                    _ => [*r; 3],
+                };
+                match keyword {
+                    "Kd" => material.diffuse = color.map(to_color_channel),
+                    "Ks" => material.specular = color.map(to_color_channel),
+                    _ => material.ambient = (color.iter().sum::<f64>() / 3.).clamp(0., 1.),
+                }
+            }
+            _ => {
+                return Err(LoadError::Parse {
+                    line: line_number,
+                    message: format!("{} has {} values", keyword, values.len()),
+                })
+            }
+        }
+    }
+    Ok(materials)
+}
+
+fn to_color_channel(value: f64) -> u8 {
+    (value.clamp(0., 1.) * 255.).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shading::new_material;
+
+    fn parse(source: &str) -> Result<Vec<(String, Material)>, LoadError> {
+        parse_mtl(source.as_bytes(), new_material())
+    }
+
+    #[test]
+    fn test_parse_mtl() {
+        let source = "# two materials\nnewmtl red\nKd 1 0 0\nKs 0.5\nNs 10\n\
+                      newmtl dull\nKa 0.2 0.4 0.6\nNs 0\nmap_Kd dull.png\n";
+        let materials = parse(source).unwrap();
+        assert_eq!(materials.len(), 2);
+        let (name, red) = &materials[0];
+        assert_eq!(name, "red");
+        assert_eq!(red.diffuse, [255, 0, 0]);
+        assert_eq!(red.specular, [128, 128, 128]);
+        assert_eq!(red.shininess, 10.);
+        let (name, dull) = &materials[1];
+        assert_eq!(name, "dull");
+        assert_eq!(dull.diffuse, new_material().diffuse);
+        assert!((dull.ambient - 0.4).abs() < 1e-12);
+        assert_eq!(dull.shininess, 1.);
+    }
+    #[test]
+    fn test_parse_mtl_errors() {
+        for (source, expected_line) in [
+            ("Kd 1 0 0\n", 1),
+            ("newmtl red\nKd 1 0\n", 2),
+            ("newmtl red\nNs shiny\n", 2),
+            ("newmtl\n", 1),
+        ] {
+            match parse(source) {
+                Err(LoadError::Parse { line, .. }) => assert_eq!(line, expected_line),
+                _ => panic!("expected a parse error for {:?}", source),
+            }
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use super::LoadError;
+use crate::geometry::{Mesh, Vector3};
+use std::collections::HashMap;
+use std::io::BufRead;
+
+///a face corner: indices of its position and, if given, its texture coordinate and normal
+type Corner = (usize, Option<usize>, Option<usize>);
+
+///reads the `v`, `vt`, `vn`, `f`, `usemtl` and `mtllib` records of a wavefront obj file, every other record is skipped
+pub fn parse_obj<R: BufRead>(reader: R) -> Result<Mesh, LoadError> {
+    let mut vertices: Vec<Vector3> = vec![];
+    let mut texture_coordinates: Vec<[f64; 2]> = vec![];
+    let mut normals: Vec<Vector3> = vec![];
+    let mut triangles: Vec<[Corner; 3]> = vec![];
+    let mut material_names: Vec<String> = vec![];
+    let mut material_ids: Vec<usize> = vec![];
+    let mut material_libraries: Vec<String> = vec![];
+    //faces before the first usemtl use the default material
+    let mut material = 0;
+
+    for (line_index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = line_index + 1;
+        //everything after a '#' is a comment
+        let content = match line.find('#') {
+            Some(position) => &line[..position],
+            None => &line,
+        };
+        let mut tokens = content.split_whitespace();
+        match tokens.next() {
+            Some("v") => vertices.push(parse_vertex(tokens, line_number)?),
+            Some("vn") => normals.push(parse_vertex(tokens, line_number)?),
+            Some("vt") => {
+                let coordinates = parse_numbers(tokens, line_number)?;
+                if coordinates.is_empty() {
+                    return Err(LoadError::Parse {
+                        line: line_number,
+                        message: "texture coordinate needs at least 1 value".to_owned(),
+                    });
+                }
+                texture_coordinates.push([coordinates[0], *coordinates.get(1).unwrap_or(&0.)]);
+            }
+            Some("usemtl") => {
+                let name = tokens.next().ok_or_else(|| LoadError::Parse {
+                    line: line_number,
+                    message: "usemtl needs a material name".to_owned(),
+                })?;
+                material = match material_names.iter().position(|known| known == name) {
+                    Some(index) => index + 1,
+                    None => {
+                        material_names.push(name.to_owned());
+                        material_names.len()
+                    }
+                };
+            }
+            Some("mtllib") => material_libraries.extend(tokens.map(str::to_owned)),
+            Some("f") => {
+                let mut face: Vec<Corner> = vec![];
+                for token in tokens {
+                    face.push(parse_corner(
+                        token,
+                        [vertices.len(), texture_coordinates.len(), normals.len()],
+                        line_number,
+                    )?);
+                }
+                if face.len() < 3 {
+                    return Err(LoadError::Parse {
+                        line: line_number,
+                        message: format!("face needs at least 3 vertices, got {}", face.len()),
+                    });
+                }
+                //fan triangulation, works for quads and convex n-gons
+                for i in 1..face.len() - 1 {
+                    triangles.push([face[0], face[i], face[i + 1]]);
+                    material_ids.push(material);
+                }
+            }
+            //o, g, s, ... carry nothing we can display
+            _ => {}
+        }
+    }
+
+    let mut mesh = build_mesh(vertices, &texture_coordinates, &normals, &triangles);
+    if !material_names.is_empty() {
+        mesh.material_ids = Some(material_ids);
+    }
+    mesh.material_names = material_names;
+    mesh.material_libraries = material_libraries;
+    Ok(mesh)
+}
+
+///obj indexes positions, texture coordinates and normals separately, a mesh shares one index for all three;
+///a position used with different texture coordinates or normals gets a copy for every further combination
+fn build_mesh(
+    vertices: Vec<Vector3>,
+    texture_coordinates: &[[f64; 2]],
+    normals: &[Vector3],
+    triangles: &[[Corner; 3]],
+) -> Mesh {
+    let mut positions = vertices;
+    let mut attributes: Vec<Option<(Option<usize>, Option<usize>)>> = vec![None; positions.len()];
+    let mut copies: HashMap<Corner, usize> = HashMap::new();
+    let mut indices: Vec<[usize; 3]> = vec![];
+    for triangle in triangles {
+        let mut indexed = [0; 3];
+        for (corner, (position, texture_coordinate, normal)) in triangle.iter().enumerate() {
+            let wanted = (*texture_coordinate, *normal);
+            indexed[corner] = match attributes[*position] {
+                None => {
+                    attributes[*position] = Some(wanted);
+                    *position
+                }
+                Some(used) if used == wanted => *position,
+                Some(_) => *copies.entry(triangle[corner]).or_insert_with(|| {
+                    positions.push(positions[*position]);
+                    attributes.push(Some(wanted));
+                    positions.len() - 1
+                }),
+            };
+        }
+        indices.push(indexed);
+    }
+
+    let corners = || triangles.iter().flatten();
+    let mut mesh = Mesh::new(positions, indices);
+    if corners().any(|(_, texture_coordinate, _)| texture_coordinate.is_some()) {
+        mesh.uvs = Some(
+            attributes
+                .iter()
+                .map(|used| match used {
+                    Some((Some(index), _)) => texture_coordinates[*index],
+                    _ => [0., 0.],
+                })
+                .collect(),
+        );
+    }
+    if corners().any(|(_, _, normal)| normal.is_some()) {
+        mesh.normals = Some(
+            attributes
+                .iter()
+                .map(|used| match used {
+                    Some((_, Some(index))) => Some(normals[*index]),
+                    _ => None,
+                })
+                .collect(),
+        );
+    }
+    mesh
+}
+
+pub(super) fn parse_numbers<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    line_number: usize,
+) -> Result<Vec<f64>, LoadError> {
+    let mut numbers: Vec<f64> = vec![];
+    for token in tokens {
+        match token.parse::<f64>() {
+            Ok(value) => numbers.push(value),
+            Err(_) => {
+                return Err(LoadError::Parse {
+                    line: line_number,
+                    message: format!("'{}' is not a number", token),
+                })
+            }
+        }
+    }
+    Ok(numbers)
+}
+
+fn parse_vertex<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    line_number: usize,
+) -> Result<Vector3, LoadError> {
+    let coordinates = parse_numbers(tokens, line_number)?;
+    if coordinates.len() < 3 {
+        return Err(LoadError::Parse {
+            line: line_number,
+            message: format!("vertex needs 3 coordinates, got {}", coordinates.len()),
+        });
+    }
+    Ok(Vector3 {
+        x: coordinates[0],
+        y: coordinates[1],
+        z: coordinates[2],
+    })
+}
+
+///turns a `v`, `v/vt`, `v//vn` or `v/vt/vn` token into zero based indices,
+///`counts` are the numbers of positions, texture coordinates and normals defined so far
+fn parse_corner(token: &str, counts: [usize; 3], line_number: usize) -> Result<Corner, LoadError> {
+    let mut parts = token.split('/');
+    let position = parse_face_index(parts.next().unwrap_or(""), token, counts[0], line_number)?;
+    let mut optional_index = |count| match parts.next() {
+        Some(part) if !part.is_empty() => {
+            parse_face_index(part, token, count, line_number).map(Some)
+        }
+        _ => Ok(None),
+    };
+    let texture_coordinate = optional_index(counts[1])?;
+    let normal = optional_index(counts[2])?;
+    Ok((position, texture_coordinate, normal))
+}
+
+fn parse_face_index(
+    part: &str,
+    token: &str,
+    count: usize,
+    line_number: usize,
+) -> Result<usize, LoadError> {
+    let index: i64 = match part.parse() {
+        Ok(index) => index,
+        Err(_) => {
+            return Err(LoadError::Parse {
+                line: line_number,
+                message: format!("'{}' is not a vertex index", token),
+            })
+        }
+    };
+    //positive indices start at 1, negative ones count back from the last record
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        count as i64 + index
+    };
+    if index == 0 || resolved < 0 || resolved >= count as i64 {
+        return Err(LoadError::IndexOutOfRange {
+            line: line_number,
+            index,
+            vertex_count: count,
+        });
+    }
+    Ok(resolved as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(source: &str) -> Result<Mesh, LoadError> {
+        parse_obj(source.as_bytes())
+    }
+
+    #[test]
+    fn test_parse_obj_triangle() {
+        let mesh = parse("v 0 0 0\nv 1 0 0\nv 0 1.5 0\nf 1 2 3\n").unwrap();
+        assert_eq!(mesh.positions.len(), 3);
+        assert_eq!(
+            mesh.positions[2],
+            Vector3 {
+                x: 0.,
+                y: 1.5,
+                z: 0.
+            }
+        );
+        assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+    }
+    #[test]
+    fn test_parse_obj_triangulates_quads_and_ngons() {
+        let mesh = parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv -1 1 0\nf 1 2 3 4\nf 1 2 3 4 5\n")
+            .unwrap();
+        assert_eq!(
+            mesh.indices,
+            vec![[0, 1, 2], [0, 2, 3], [0, 1, 2], [0, 2, 3], [0, 3, 4]]
+        );
+    }
+    #[test]
+    fn test_parse_obj_negative_indices() {
+        let mesh = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\nv 0 0 1\nf -1 -2 -3\n").unwrap();
+        assert_eq!(mesh.indices, vec![[0, 1, 2], [3, 2, 1]]);
+    }
+    #[test]
+    fn test_parse_obj_texture_and_normal_records() {
+        let source = "# comment\no thing\nv 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0.5 0.25\nvn 0 0 1\n\
+                      s off\nf 1/1/1 2/1/1 3//1 # trailing comment\n";
+        let mesh = parse(source).unwrap();
+        assert_eq!(mesh.positions.len(), 3);
+        assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+        assert_eq!(mesh.uvs.unwrap(), vec![[0.5, 0.25], [0.5, 0.25], [0., 0.]]);
+        let up = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 1.,
+        };
+        assert_eq!(mesh.normals.unwrap(), vec![Some(up), Some(up), Some(up)]);
+        assert!(mesh.material_ids.is_none());
+        //corners without a normal are marked as such, not given a made up one
+        let partial = parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\nf 1//1 2 3\n").unwrap();
+        assert_eq!(partial.normals.unwrap(), vec![Some(up), None, None]);
+    }
+    #[test]
+    fn test_parse_obj_splits_vertices_on_seams() {
+        //the shared edge 1-3 has a different normal on either side, like the edge of a cube
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nv 0 0 1\nvn 0 0 1\nvn 1 0 0\n\
+                      f 1//1 2//1 3//1\nf 1//2 3//2 4//2\nf 3//2 1//2 4//2\n";
+        let mesh = parse(source).unwrap();
+        assert_eq!(mesh.indices, vec![[0, 1, 2], [4, 5, 3], [5, 4, 3]]);
+        assert_eq!(mesh.positions.len(), 6);
+        assert_eq!(mesh.positions[4], mesh.positions[0]);
+        assert_eq!(mesh.positions[5], mesh.positions[2]);
+        let normals = mesh.normals.unwrap();
+        assert_eq!(normals[2].unwrap().z, 1.);
+        assert_eq!(normals[5].unwrap().x, 1.);
+    }
+    #[test]
+    fn test_parse_obj_materials() {
+        let source = "mtllib colors.mtl more.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nf 1 2 3\nusemtl red\nf 1 2 3 4\n\
+                      usemtl blue\nf 2 3 4\nusemtl red\nf 1 3 4\n";
+        let mesh = parse(source).unwrap();
+        assert_eq!(mesh.material_ids.unwrap(), vec![0, 1, 1, 2, 1]);
+        assert_eq!(mesh.material_names, vec!["red", "blue"]);
+        assert_eq!(mesh.material_libraries, vec!["colors.mtl", "more.mtl"]);
+    }
+    #[test]
+    fn test_parse_obj_malformed_vertex() {
+        match parse("v 0 0 0\nv 1 zero 0\n") {
+            Err(LoadError::Parse { line, .. }) => assert_eq!(line, 2),
+            _ => panic!("expected a parse error"),
+        }
+        match parse("v 0 0\n") {
+            Err(LoadError::Parse { line, .. }) => assert_eq!(line, 1),
+            _ => panic!("expected a parse error"),
+        }
+        match parse("v 0 0 0\nusemtl\nf 1 1 1\n") {
+            Err(LoadError::Parse { line, .. }) => assert_eq!(line, 2),
+            _ => panic!("expected a parse error"),
+        }
+    }
+    #[test]
+    fn test_parse_obj_index_out_of_range() {
+        match parse("v 0 0 0\nv 1 0 0\nv 0 1 0\n\nf 1 2 4\n") {
+            Err(LoadError::IndexOutOfRange {
+                line,
+                index,
+                vertex_count,
+            }) => {
+                assert_eq!(line, 5);
+                assert_eq!(index, 4);
+                assert_eq!(vertex_count, 3);
+            }
+            _ => panic!("expected an index error"),
+        }
+        assert!(matches!(
+            parse("v 0 0 0\nf 0 1 1\n"),
+            Err(LoadError::IndexOutOfRange { index: 0, .. })
+        ));
+        assert!(matches!(
+            parse("v 0 0 0\nf -1 -1 -2\n"),
+            Err(LoadError::IndexOutOfRange { index: -2, .. })
+        ));
+        assert!(matches!(
+            parse("v 0 0 0\nvt 0 0\nf 1/1 1/2 1/1\n"),
+            Err(LoadError::IndexOutOfRange {
+                index: 2,
+                vertex_count: 1,
+                ..
+            })
+        ));
+    }
+    #[test]
+    fn test_parse_obj_degenerate_face() {
+        assert!(matches!(
+            parse("v 0 0 0\nv 1 0 0\nf 1 2\n"),
+            Err(LoadError::Parse { line: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,736 @@
+use super::LoadError;
+use crate::geometry::{Mesh, Vector3};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+impl ScalarType {
+    fn parse(name: &str) -> Option<ScalarType> {
+        match name {
+            "char" | "int8" => Some(ScalarType::I8),
+            "uchar" | "uint8" => Some(ScalarType::U8),
+            "short" | "int16" => Some(ScalarType::I16),
+            "ushort" | "uint16" => Some(ScalarType::U16),
+            "int" | "int32" => Some(ScalarType::I32),
+            "uint" | "uint32" => Some(ScalarType::U32),
+            "float" | "float32" => Some(ScalarType::F32),
+            "double" | "float64" => Some(ScalarType::F64),
+            _ => None,
+        }
+    }
+    fn size(&self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+    fn is_float(&self) -> bool {
+        matches!(self, ScalarType::F32 | ScalarType::F64)
+    }
+}
+
+#[derive(Debug)]
+enum Property {
+    Scalar {
+        name: String,
+        value_type: ScalarType,
+    },
+    List {
+        name: String,
+        count_type: ScalarType,
+        item_type: ScalarType,
+    },
+}
+impl Property {
+    fn name(&self) -> &str {
+        match self {
+            Property::Scalar { name, .. } | Property::List { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+struct Header {
+    format: Format,
+    elements: Vec<Element>,
+    ///number of header lines, the body starts on the line after
+    line_count: usize,
+    ///byte offset of the body
+    body_start: usize,
+}
+
+///reads an ascii or binary (little or big endian) ply file with optional vertex colors, normals and texture coordinates
+pub fn parse_ply(bytes: &[u8]) -> Result<Mesh, LoadError> {
+    let header = parse_header(bytes)?;
+    let mut body = match header.format {
+        Format::Ascii => Body::Ascii(AsciiBody::new(
+            &bytes[header.body_start..],
+            header.line_count,
+        )?),
+        format => Body::Binary(BinaryBody {
+            bytes: &bytes[header.body_start..],
+            position: 0,
+            big_endian: format == Format::BinaryBigEndian,
+        }),
+    };
+
+    let mut vertices: Vec<Vector3> = vec![];
+    let mut colors: Vec<[u8; 3]> = vec![];
+    let mut normals: Vec<Option<Vector3>> = vec![];
+    let mut uvs: Vec<[f64; 2]> = vec![];
+    let mut polygons: Vec<[usize; 3]> = vec![];
+    let mut has_colors = false;
+    let mut has_normals = false;
+    let mut has_uvs = false;
+    for element in &header.elements {
+        match element.name.as_str() {
+            "vertex" => {
+                let position_of = |name: &str| {
+                    element
+                        .properties
+                        .iter()
+                        .position(|property| property.name() == name)
+                };
+                //a list where a single value is expected has no value to read
+                let read_as_values = [
+                    "x",
+                    "y",
+                    "z",
+                    "red",
+                    "green",
+                    "blue",
+                    "nx",
+                    "ny",
+                    "nz",
+                    "u",
+                    "v",
+                    "s",
+                    "t",
+                    "texture_u",
+                    "texture_v",
+                ];
+                if let Some(property) = element.properties.iter().find(|property| {
+                    matches!(property, Property::List { .. })
+                        && read_as_values.contains(&property.name())
+                }) {
+                    return Err(LoadError::Malformed(format!(
+                        "vertex property '{}' must not be a list",
+                        property.name()
+                    )));
+                }
+                let (x, y, z) = match (position_of("x"), position_of("y"), position_of("z")) {
+                    (Some(x), Some(y), Some(z)) => (x, y, z),
+                    _ => {
+                        return Err(LoadError::Malformed(
+                            "vertex element needs x, y and z properties".to_owned(),
+                        ))
+                    }
+                };
+                let color_channels = match (
+                    position_of("red"),
+                    position_of("green"),
+                    position_of("blue"),
+                ) {
+                    (Some(r), Some(g), Some(b)) => Some([r, g, b]),
+                    _ => None,
+                };
+                has_colors = color_channels.is_some();
+                let normal_axes = match (position_of("nx"), position_of("ny"), position_of("nz")) {
+                    (Some(x), Some(y), Some(z)) => Some((x, y, z)),
+                    _ => None,
+                };
+                has_normals = normal_axes.is_some();
+                //exporters disagree on what to call texture coordinates
+                let uv_axes = [("u", "v"), ("s", "t"), ("texture_u", "texture_v")]
+                    .iter()
+                    .find_map(|(u, v)| match (position_of(u), position_of(v)) {
+                        (Some(u), Some(v)) => Some((u, v)),
+                        _ => None,
+                    });
+                has_uvs = uv_axes.is_some();
+
+                for _ in 0..element.count {
+                    body.next_record()?;
+                    let mut values: Vec<f64> = vec![];
+                    for property in &element.properties {
+                        match property {
+                            Property::Scalar { value_type, .. } => {
+                                values.push(body.read(*value_type)?)
+                            }
+                            Property::List {
+                                count_type,
+                                item_type,
+                                ..
+                            } => {
+                                body.skip_list(*count_type, *item_type)?;
+                                //keeps `values` indexed like `element.properties`
+                                values.push(f64::NAN);
+                            }
+                        }
+                    }
+                    vertices.push(Vector3 {
+                        x: values[x],
+                        y: values[y],
+                        z: values[z],
+                    });
+                    if let Some(channels) = color_channels {
+                        let mut color = [0u8; 3];
+                        for (channel, property_index) in channels.iter().enumerate() {
+                            color[channel] = to_color_channel(
+                                values[*property_index],
+                                &element.properties[*property_index],
+                            );
+                        }
+                        colors.push(color);
+                    }
+                    if let Some((x, y, z)) = normal_axes {
+                        //a nan or infinite normal can't shade anything, treat it as missing
+                        let normal = [values[x], values[y], values[z]];
+                        normals.push(normal.iter().all(|value| value.is_finite()).then_some(
+                            Vector3 {
+                                x: normal[0],
+                                y: normal[1],
+                                z: normal[2],
+                            },
+                        ));
+                    }
+                    if let Some((u, v)) = uv_axes {
+                        uvs.push([values[u], values[v]]);
+                    }
+                }
+            }
+            "face" => {
+                for _ in 0..element.count {
+                    let line = body.next_record()?;
+                    for property in &element.properties {
+                        match property {
+                            Property::List {
+                                name,
+                                count_type,
+                                item_type,
+                            } if name == "vertex_indices" || name == "vertex_index" => {
+                                let count = body.read(*count_type)? as usize;
+                                let mut face: Vec<usize> = vec![];
+                                for _ in 0..count {
+                                    let index = body.read(*item_type)? as i64;
+                                    if index < 0 || index as usize >= vertices.len() {
+                                        return Err(out_of_range(line, index, vertices.len()));
+                                    }
+                                    face.push(index as usize);
+                                }
+                                if face.len() < 3 {
+                                    return Err(LoadError::Malformed(format!(
+                                        "face needs at least 3 vertices, got {}",
+                                        face.len()
+                                    )));
+                                }
+                                for i in 1..face.len() - 1 {
+                                    polygons.push([face[0], face[i], face[i + 1]]);
+                                }
+                            }
+                            Property::List {
+                                count_type,
+                                item_type,
+                                ..
+                            } => body.skip_list(*count_type, *item_type)?,
+                            Property::Scalar { value_type, .. } => {
+                                body.read(*value_type)?;
+                            }
+                        }
+                    }
+                }
+            }
+            //edges, materials, ... are read past but not used
+            _ => {
+                for _ in 0..element.count {
+                    body.next_record()?;
+                    for property in &element.properties {
+                        match property {
+                            Property::Scalar { value_type, .. } => {
+                                body.read(*value_type)?;
+                            }
+                            Property::List {
+                                count_type,
+                                item_type,
+                                ..
+                            } => body.skip_list(*count_type, *item_type)?,
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut mesh = Mesh::new(vertices, polygons);
+    if has_colors {
+        mesh.colors = Some(colors);
+    }
+    if has_normals {
+        mesh.normals = Some(normals);
+    }
+    if has_uvs {
+        mesh.uvs = Some(uvs);
+    }
+    Ok(mesh)
+}
+
+///integer channels are 0-255, float channels 0-1
+fn to_color_channel(value: f64, property: &Property) -> u8 {
+    let is_float = match property {
+        Property::Scalar { value_type, .. } => value_type.is_float(),
+        Property::List { .. } => false,
+    };
+    let value = if is_float { value * 255. } else { value };
+    value.round().clamp(0., 255.) as u8
+}
+
+///binary files have no lines, the error names the face instead
+fn out_of_range(line: Option<usize>, index: i64, vertex_count: usize) -> LoadError {
+    match line {
+        Some(line) => LoadError::IndexOutOfRange {
+            line,
+            index,
+            vertex_count,
+        },
+        None => LoadError::Malformed(format!(
+            "vertex index {} is out of range ({} vertices defined)",
+            index, vertex_count
+        )),
+    }
+}
+
+fn parse_header(bytes: &[u8]) -> Result<Header, LoadError> {
+    let mut format: Option<Format> = None;
+    let mut elements: Vec<Element> = vec![];
+    let mut position = 0;
+    let mut line_number = 0;
+    loop {
+        let line_end = match bytes[position..].iter().position(|byte| *byte == b'\n') {
+            Some(offset) => position + offset,
+            None => {
+                return Err(LoadError::Malformed(
+                    "ply header is missing 'end_header'".to_owned(),
+                ))
+            }
+        };
+        line_number += 1;
+        let line = String::from_utf8_lossy(&bytes[position..line_end]);
+        position = line_end + 1;
+        let parse_error = |message: String| LoadError::Parse {
+            line: line_number,
+            message,
+        };
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if line_number == 1 {
+            if tokens != ["ply"] {
+                return Err(parse_error("file does not start with 'ply'".to_owned()));
+            }
+            continue;
+        }
+        match tokens.first().copied() {
+            Some("format") => {
+                format = match tokens.get(1).copied() {
+                    Some("ascii") => Some(Format::Ascii),
+                    Some("binary_little_endian") => Some(Format::BinaryLittleEndian),
+                    Some("binary_big_endian") => Some(Format::BinaryBigEndian),
+                    other => {
+                        return Err(parse_error(format!(
+                            "unknown format '{}'",
+                            other.unwrap_or("")
+                        )))
+                    }
+                }
+            }
+            Some("element") => {
+                let count = match (tokens.get(1), tokens.get(2).map(|count| count.parse())) {
+                    (Some(_), Some(Ok(count))) => count,
+                    _ => return Err(parse_error("expected 'element <name> <count>'".to_owned())),
+                };
+                elements.push(Element {
+                    name: tokens[1].to_owned(),
+                    count,
+                    properties: vec![],
+                });
+            }
+            Some("property") => {
+                let property = match tokens[1..] {
+                    ["list", count_type, item_type, name] => {
+                        match (ScalarType::parse(count_type), ScalarType::parse(item_type)) {
+                            (Some(count_type), Some(item_type)) => Property::List {
+                                name: name.to_owned(),
+                                count_type,
+                                item_type,
+                            },
+                            _ => {
+                                return Err(parse_error(format!("unknown list type in '{}'", line)))
+                            }
+                        }
+                    }
+                    [value_type, name] => match ScalarType::parse(value_type) {
+                        Some(value_type) => Property::Scalar {
+                            name: name.to_owned(),
+                            value_type,
+                        },
+                        None => return Err(parse_error(format!("unknown type '{}'", value_type))),
+                    },
+                    _ => return Err(parse_error(format!("malformed property '{}'", line))),
+                };
+                match elements.last_mut() {
+                    Some(element) => element.properties.push(property),
+                    None => return Err(parse_error("property before any element".to_owned())),
+                }
+            }
+            Some("end_header") => break,
+            //comment, obj_info and empty lines
+            _ => {}
+        }
+    }
+    match format {
+        Some(format) => Ok(Header {
+            format,
+            elements,
+            line_count: line_number,
+            body_start: position,
+        }),
+        None => Err(LoadError::Malformed(
+            "ply header has no 'format' line".to_owned(),
+        )),
+    }
+}
+
+enum Body<'a> {
+    Ascii(AsciiBody<'a>),
+    Binary(BinaryBody<'a>),
+}
+impl Body<'_> {
+    ///moves to the next element instance, returns its line for ascii files
+    fn next_record(&mut self) -> Result<Option<usize>, LoadError> {
+        match self {
+            Body::Ascii(body) => body.next_line().map(Some),
+            Body::Binary(_) => Ok(None),
+        }
+    }
+    fn read(&mut self, value_type: ScalarType) -> Result<f64, LoadError> {
+        match self {
+            Body::Ascii(body) => body.read(),
+            Body::Binary(body) => body.read(value_type),
+        }
+    }
+    fn skip_list(
+        &mut self,
+        count_type: ScalarType,
+        item_type: ScalarType,
+    ) -> Result<(), LoadError> {
+        let count = self.read(count_type)? as usize;
+        for _ in 0..count {
+            self.read(item_type)?;
+        }
+        Ok(())
+    }
+}
+
+struct AsciiBody<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    tokens: std::str::SplitWhitespace<'a>,
+    first_line: usize,
+    line_number: usize,
+}
+impl<'a> AsciiBody<'a> {
+    fn new(bytes: &'a [u8], header_lines: usize) -> Result<AsciiBody<'a>, LoadError> {
+        let source = match std::str::from_utf8(bytes) {
+            Ok(source) => source,
+            Err(_) => {
+                return Err(LoadError::Malformed(
+                    "ascii ply contains invalid utf-8".to_owned(),
+                ))
+            }
+        };
+        Ok(AsciiBody {
+            lines: source.lines().enumerate(),
+            tokens: "".split_whitespace(),
+            first_line: header_lines + 1,
+            line_number: header_lines,
+        })
+    }
+    fn next_line(&mut self) -> Result<usize, LoadError> {
+        for (index, line) in self.lines.by_ref() {
+            if !line.trim().is_empty() {
+                self.line_number = self.first_line + index;
+                self.tokens = line.split_whitespace();
+                return Ok(self.line_number);
+            }
+        }
+        Err(LoadError::Malformed(
+            "ply file ends before all elements were read".to_owned(),
+        ))
+    }
+    fn read(&mut self) -> Result<f64, LoadError> {
+        match self.tokens.next() {
+            Some(token) => token.parse().map_err(|_| LoadError::Parse {
+                line: self.line_number,
+                message: format!("'{}' is not a number", token),
+            }),
+            None => Err(LoadError::Parse {
+                line: self.line_number,
+                message: "line has fewer values than the header declares".to_owned(),
+            }),
+        }
+    }
+}
+
+struct BinaryBody<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    big_endian: bool,
+}
+impl BinaryBody<'_> {
+    fn read(&mut self, value_type: ScalarType) -> Result<f64, LoadError> {
+        let size = value_type.size();
+        if self.position + size > self.bytes.len() {
+            return Err(LoadError::Malformed(
+                "ply file ends before all elements were read".to_owned(),
+            ));
+        }
+        let mut raw = [0u8; 8];
+        raw[..size].copy_from_slice(&self.bytes[self.position..self.position + size]);
+        self.position += size;
+        if self.big_endian {
+            raw[..size].reverse();
+        }
+        Ok(match value_type {
+            ScalarType::I8 => raw[0] as i8 as f64,
+            ScalarType::U8 => raw[0] as f64,
+            ScalarType::I16 => i16::from_le_bytes([raw[0], raw[1]]) as f64,
+            ScalarType::U16 => u16::from_le_bytes([raw[0], raw[1]]) as f64,
+            ScalarType::I32 => i32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            ScalarType::U32 => u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            ScalarType::F32 => f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]) as f64,
+            ScalarType::F64 => f64::from_le_bytes(raw),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASCII_QUAD: &str = "ply
+format ascii 1.0
+comment a colored quad
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar int vertex_indices
+end_header
+0 0 0 255 0 0
+1 0 0 0 255 0
+1 1 0 0 0 255
+0 1 0 10 20 30
+4 0 1 2 3
+";
+
+    fn binary_triangle(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian {
+            "binary_big_endian"
+        } else {
+            "binary_little_endian"
+        };
+        let mut bytes = format!(
+            "ply\nformat {} 1.0\nelement vertex 3\nproperty double x\nproperty double y\n\
+             property double z\nproperty float red\nproperty float green\nproperty float blue\n\
+             element edge 1\nproperty int vertex1\nproperty int vertex2\n\
+             element face 1\nproperty list uchar uint vertex_index\nend_header\n",
+            format
+        )
+        .into_bytes();
+        let vertices: [[f64; 3]; 3] = [[0., 0., 0.], [2., 0., 0.], [0., -3.5, 0.]];
+        for vertex in vertices {
+            for value in vertex {
+                if big_endian {
+                    bytes.extend_from_slice(&value.to_be_bytes());
+                } else {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            for channel in [1f32, 0.5, 0.] {
+                if big_endian {
+                    bytes.extend_from_slice(&channel.to_be_bytes());
+                } else {
+                    bytes.extend_from_slice(&channel.to_le_bytes());
+                }
+            }
+        }
+        for value in [0i32, 1] {
+            if big_endian {
+                bytes.extend_from_slice(&value.to_be_bytes());
+            } else {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        bytes.push(3);
+        for index in [0u32, 1, 2] {
+            if big_endian {
+                bytes.extend_from_slice(&index.to_be_bytes());
+            } else {
+                bytes.extend_from_slice(&index.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_parse_ply_ascii_with_colors() {
+        let mesh = parse_ply(ASCII_QUAD.as_bytes()).unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.indices, vec![[0, 1, 2], [0, 2, 3]]);
+        assert_eq!(
+            mesh.colors.unwrap(),
+            vec![[255, 0, 0], [0, 255, 0], [0, 0, 255], [10, 20, 30]]
+        );
+    }
+    #[test]
+    fn test_parse_ply_binary_both_endians() {
+        for big_endian in [false, true] {
+            let mesh = parse_ply(&binary_triangle(big_endian)).unwrap();
+            assert_eq!(
+                mesh.positions[2],
+                Vector3 {
+                    x: 0.,
+                    y: -3.5,
+                    z: 0.
+                }
+            );
+            assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+            assert_eq!(mesh.colors.unwrap()[1], [255, 128, 0]);
+        }
+    }
+    #[test]
+    fn test_parse_ply_without_colors() {
+        let source =
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\n\
+                      property float z\nelement face 1\nproperty list uchar int vertex_indices\n\
+                      end_header\n0 0 0\n1 0 0\n0 1 0\n3 0 1 2\n";
+        let mesh = parse_ply(source.as_bytes()).unwrap();
+        assert!(mesh.colors.is_none());
+        assert!(mesh.normals.is_none() && mesh.uvs.is_none());
+        assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+    }
+    #[test]
+    fn test_parse_ply_normals_and_texture_coordinates() {
+        let source =
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\n\
+                      property float z\nproperty float nx\nproperty float ny\nproperty float nz\n\
+                      property float s\nproperty float t\nelement face 1\n\
+                      property list uchar int vertex_indices\nend_header\n\
+                      0 0 0 0 0 1 0 0\n1 0 0 0 0 1 1 0\n0 1 0 0 1 0 0 0.5\n3 0 1 2\n";
+        let mesh = parse_ply(source.as_bytes()).unwrap();
+        assert_eq!(
+            mesh.normals.unwrap()[2],
+            Some(Vector3 {
+                x: 0.,
+                y: 1.,
+                z: 0.
+            })
+        );
+        assert_eq!(mesh.uvs.unwrap(), vec![[0., 0.], [1., 0.], [0., 0.5]]);
+    }
+    #[test]
+    fn test_parse_ply_texture_coordinate_spellings() {
+        for (u, v) in [("u", "v"), ("texture_u", "texture_v")] {
+            let source = format!(
+                "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\n\
+                 property float z\nproperty float {u}\nproperty float {v}\nelement face 1\n\
+                 property list uchar int vertex_indices\nend_header\n\
+                 0 0 0 0 0\n1 0 0 1 0\n0 1 0 0.25 0.75\n3 0 1 2\n"
+            );
+            let mesh = parse_ply(source.as_bytes()).unwrap();
+            assert_eq!(mesh.uvs.unwrap(), vec![[0., 0.], [1., 0.], [0.25, 0.75]]);
+        }
+    }
+    #[test]
+    fn test_parse_ply_non_finite_normals_are_missing() {
+        let source =
+            "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\n\
+                      property float z\nproperty float nx\nproperty float ny\nproperty float nz\n\
+                      element face 1\nproperty list uchar int vertex_indices\nend_header\n\
+                      0 0 0 0 0 1\n1 0 0 nan 0 1\n0 1 0 0 inf 0\n3 0 1 2\n";
+        let normals = parse_ply(source.as_bytes()).unwrap().normals.unwrap();
+        assert!(normals[0].is_some());
+        assert!(normals[1].is_none() && normals[2].is_none());
+    }
+    #[test]
+    fn test_parse_ply_errors() {
+        let bad_index = ASCII_QUAD.replace("4 0 1 2 3", "4 0 1 2 7");
+        assert!(matches!(
+            parse_ply(bad_index.as_bytes()),
+            Err(LoadError::IndexOutOfRange {
+                line: 18,
+                index: 7,
+                vertex_count: 4
+            })
+        ));
+        let bad_number = ASCII_QUAD.replace("1 1 0 0 0 255", "1 x 0 0 0 255");
+        assert!(matches!(
+            parse_ply(bad_number.as_bytes()),
+            Err(LoadError::Parse { line: 16, .. })
+        ));
+        let mut truncated = binary_triangle(false);
+        truncated.truncate(truncated.len() - 2);
+        assert!(matches!(
+            parse_ply(&truncated),
+            Err(LoadError::Malformed(_))
+        ));
+        assert!(matches!(
+            parse_ply(b"ply\nformat ascii 1.0\n"),
+            Err(LoadError::Malformed(_))
+        ));
+        //a list before the coordinates must not shift them
+        let header = "ply\nformat ascii 1.0\nelement vertex 3\nproperty list uchar int tags\n\
+                      property float x\nproperty float y\nproperty float z\nelement face 1\n\
+                      property list uchar int vertex_indices\nend_header\n";
+        let leading_list = format!("{header}2 7 8 0 0 0\n0 1 0 0\n1 9 0 1 0\n3 0 1 2\n");
+        let mesh = parse_ply(leading_list.as_bytes()).unwrap();
+        assert_eq!(
+            mesh.positions[2],
+            Vector3 {
+                x: 0.,
+                y: 1.,
+                z: 0.
+            }
+        );
+        let list_coordinate = header.replace("property float x", "property list uchar float x");
+        assert!(matches!(
+            parse_ply(list_coordinate.as_bytes()),
+            Err(LoadError::Malformed(_))
+        ));
+    }
+}
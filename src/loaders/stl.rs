@@ -0,0 +1,292 @@
+use super::LoadError;
+use crate::geometry::{Mesh, Vector3};
+use std::collections::HashMap;
+
+const HEADER_SIZE: usize = 84;
+const FACET_SIZE: usize = 50;
+
+///reads an ascii or binary stl file, identical corners are merged into one vertex
+pub fn parse_stl(bytes: &[u8]) -> Result<Mesh, LoadError> {
+    if is_binary(bytes) {
+        parse_binary(bytes)
+    } else {
+        parse_ascii(bytes)
+    }
+}
+
+///binary files may also start with "solid", so the size the header announces is checked first
+fn is_binary(bytes: &[u8]) -> bool {
+    if bytes.len() >= HEADER_SIZE {
+        let facet_count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+        if HEADER_SIZE + facet_count * FACET_SIZE == bytes.len() {
+            return true;
+        }
+    }
+    let start = bytes
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    !bytes[start..].starts_with(b"solid")
+}
+
+///collects facets and merges their corners into a shared vertex list
+struct MeshBuilder {
+    vertices: Vec<Vector3>,
+    polygons: Vec<[usize; 3]>,
+    face_normals: Vec<Vector3>,
+    lookup: HashMap<[u64; 3], usize>,
+}
+impl MeshBuilder {
+    fn new() -> MeshBuilder {
+        MeshBuilder {
+            vertices: vec![],
+            polygons: vec![],
+            face_normals: vec![],
+            lookup: HashMap::new(),
+        }
+    }
+    fn vertex_index(&mut self, vertex: Vector3) -> usize {
+        //+ 0. turns -0.0 into 0.0 so both end up in the same vertex
+        let key = [
+            (vertex.x + 0.).to_bits(),
+            (vertex.y + 0.).to_bits(),
+            (vertex.z + 0.).to_bits(),
+        ];
+        let next_index = self.vertices.len();
+        let index = *self.lookup.entry(key).or_insert(next_index);
+        if index == next_index {
+            self.vertices.push(vertex);
+        }
+        index
+    }
+    fn push_facet(&mut self, normal: Vector3, corners: [Vector3; 3]) {
+        let polygon = [
+            self.vertex_index(corners[0]),
+            self.vertex_index(corners[1]),
+            self.vertex_index(corners[2]),
+        ];
+        self.polygons.push(polygon);
+        self.face_normals.push(normal);
+    }
+    fn build(self) -> Mesh {
+        let mut mesh = Mesh::new(self.vertices, self.polygons);
+        mesh.face_normals = Some(self.face_normals);
+        mesh
+    }
+}
+
+fn parse_binary(bytes: &[u8]) -> Result<Mesh, LoadError> {
+    if bytes.len() < HEADER_SIZE {
+        return Err(LoadError::Malformed(format!(
+            "binary stl needs a {} byte header, file has {} bytes",
+            HEADER_SIZE,
+            bytes.len()
+        )));
+    }
+    let facet_count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    let expected = HEADER_SIZE + facet_count * FACET_SIZE;
+    if bytes.len() < expected {
+        return Err(LoadError::Malformed(format!(
+            "binary stl announces {} facets ({} bytes), file has {} bytes",
+            facet_count,
+            expected,
+            bytes.len()
+        )));
+    }
+
+    let read_vector = |offset: usize| {
+        let read_f32 = |at: usize| {
+            f32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as f64
+        };
+        Vector3 {
+            x: read_f32(offset),
+            y: read_f32(offset + 4),
+            z: read_f32(offset + 8),
+        }
+    };
+    let mut builder = MeshBuilder::new();
+    for facet in 0..facet_count {
+        //normal, three corners and a two byte attribute count
+        let offset = HEADER_SIZE + facet * FACET_SIZE;
+        builder.push_facet(
+            read_vector(offset),
+            [
+                read_vector(offset + 12),
+                read_vector(offset + 24),
+                read_vector(offset + 36),
+            ],
+        );
+    }
+    Ok(builder.build())
+}
+
+fn parse_ascii(bytes: &[u8]) -> Result<Mesh, LoadError> {
+    let source = match std::str::from_utf8(bytes) {
+        Ok(source) => source,
+        Err(_) => {
+            return Err(LoadError::Malformed(
+                "ascii stl contains invalid utf-8".to_owned(),
+            ))
+        }
+    };
+
+    let mut builder = MeshBuilder::new();
+    let mut normal: Option<Vector3> = None;
+    let mut corners: Vec<Vector3> = vec![];
+    for (line_index, line) in source.lines().enumerate() {
+        let line_number = line_index + 1;
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("facet") => {
+                if tokens.next() != Some("normal") {
+                    return Err(LoadError::Parse {
+                        line: line_number,
+                        message: "expected 'facet normal'".to_owned(),
+                    });
+                }
+                normal = Some(parse_coordinates(tokens, line_number)?);
+                corners.clear();
+            }
+            Some("vertex") => corners.push(parse_coordinates(tokens, line_number)?),
+            Some("endfacet") => {
+                let facet_normal = match normal.take() {
+                    Some(facet_normal) => facet_normal,
+                    None => {
+                        return Err(LoadError::Parse {
+                            line: line_number,
+                            message: "'endfacet' without 'facet normal'".to_owned(),
+                        })
+                    }
+                };
+                if corners.len() != 3 {
+                    return Err(LoadError::Parse {
+                        line: line_number,
+                        message: format!("facet needs 3 vertices, got {}", corners.len()),
+                    });
+                }
+                builder.push_facet(facet_normal, [corners[0], corners[1], corners[2]]);
+            }
+            //solid, outer loop, endloop, endsolid only structure the file
+            _ => {}
+        }
+    }
+    Ok(builder.build())
+}
+
+fn parse_coordinates<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    line_number: usize,
+) -> Result<Vector3, LoadError> {
+    let mut coordinates: Vec<f64> = vec![];
+    for token in tokens {
+        match token.parse::<f64>() {
+            Ok(value) => coordinates.push(value),
+            Err(_) => {
+                return Err(LoadError::Parse {
+                    line: line_number,
+                    message: format!("'{}' is not a number", token),
+                })
+            }
+        }
+    }
+    if coordinates.len() != 3 {
+        return Err(LoadError::Parse {
+            line: line_number,
+            message: format!("expected 3 coordinates, got {}", coordinates.len()),
+        });
+    }
+    Ok(Vector3 {
+        x: coordinates[0],
+        y: coordinates[1],
+        z: coordinates[2],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ASCII_SQUARE: &str = "solid square
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 0 0
+      vertex 1 1 0
+    endloop
+  endfacet
+  facet normal 0 0 1
+    outer loop
+      vertex 0 0 0
+      vertex 1 1 0
+      vertex 0 1 0
+    endloop
+  endfacet
+endsolid square
+";
+
+    fn binary_stl(header: &[u8], facets: &[[f32; 12]]) -> Vec<u8> {
+        let mut bytes = vec![0u8; 80];
+        bytes[..header.len()].copy_from_slice(header);
+        bytes.extend_from_slice(&(facets.len() as u32).to_le_bytes());
+        for facet in facets {
+            for value in facet {
+                bytes.extend_from_slice(&value.to_le_bytes());
+            }
+            bytes.extend_from_slice(&[0, 0]);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_parse_stl_ascii_merges_vertices() {
+        let mesh = parse_stl(ASCII_SQUARE.as_bytes()).unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.indices, vec![[0, 1, 2], [0, 2, 3]]);
+        let normals = mesh.face_normals.unwrap();
+        assert_eq!(normals.len(), 2);
+        assert_eq!(
+            normals[1],
+            Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 1.
+            }
+        );
+    }
+    #[test]
+    fn test_parse_stl_binary() {
+        let facets = [
+            [0., 0., -1., 0., 0., 0., 1., 0., 0., 0., 1., 0.],
+            [0., 0., -1., 1., 0., 0., -0., 1., 0., 1., 1., 0.],
+        ];
+        let mesh = parse_stl(&binary_stl(b"binary", &facets)).unwrap();
+        assert_eq!(mesh.positions.len(), 4);
+        assert_eq!(mesh.indices, vec![[0, 1, 2], [1, 2, 3]]);
+        assert_eq!(mesh.face_normals.unwrap()[0].z, -1.);
+    }
+    #[test]
+    fn test_parse_stl_binary_starting_with_solid() {
+        let facets = [[0., 0., 1., 0., 0., 0., 1., 0., 0., 0., 1., 0.]];
+        let mesh = parse_stl(&binary_stl(b"solid but binary", &facets)).unwrap();
+        assert_eq!(mesh.indices, vec![[0, 1, 2]]);
+    }
+    #[test]
+    fn test_parse_stl_truncated_binary() {
+        let mut bytes = binary_stl(b"", &[[0.; 12]]);
+        bytes[80] = 2;
+        assert!(matches!(parse_stl(&bytes), Err(LoadError::Malformed(_))));
+    }
+    #[test]
+    fn test_parse_stl_ascii_errors() {
+        let bad_number = ASCII_SQUARE.replace("vertex 1 1 0", "vertex 1 one 0");
+        assert!(matches!(
+            parse_stl(bad_number.as_bytes()),
+            Err(LoadError::Parse { line: 6, .. })
+        ));
+        let missing_vertex = ASCII_SQUARE.replacen("      vertex 1 0 0\n", "", 1);
+        assert!(matches!(
+            parse_stl(missing_vertex.as_bytes()),
+            Err(LoadError::Parse { line: 7, .. })
+        ));
+    }
+}
@@ -1,64 +1,340 @@
-use core::time;
-use geometry::{TransformMatrix, Vector3};
+use geometry::{EulerOrder, Quaternion, TransformMatrix, Vector3};
+use std::path::Path;
+use std::time;
 
 mod camera;
 mod geometry;
+mod loaders;
 mod objects;
+mod presenter;
+mod render;
+mod shading;
+mod terminal;
 
-///creates a single 'snapshot' buffer of chars
-fn test_snapshot() {
-    let mut obj: objects::Object = objects::new_test_obj();
-    let main_camera = camera::new_camera();
-    let one_degree_transform = TransformMatrix {
-        row_1: Vector3 {
-            x: 0.95,
-            y: 0.,
-            z: 0.0975,
+///radius loaded models are scaled to, roughly the size of the test object
+const MODEL_RADIUS: f64 = 50.;
+const FRAME_DELAY_MS: u64 = 1000;
+const RESIZE_POLL_MS: u64 = 20;
+
+///what the command line asked for
+struct Options {
+    model: Option<String>,
+    light: shading::Light,
+    ///flat, or smooth across the polygons
+    shading: shading::Shading,
+    ///what the model is made of
+    material: shading::Material,
+    render: render::Settings,
+    ///overrides the pixel aspect the output backend implies
+    pixel_aspect: Option<f64>,
+    ///only redraw the cells that changed
+    diff: bool,
+    ///degrees the model turns every `FRAME_DELAY_MS` around `spin_axis`
+    spin: f64,
+    spin_axis: Vector3,
+    ///starting orientation in degrees around x, y and z, applied in `tilt_order`
+    tilt: Vector3,
+    tilt_order: EulerOrder,
+}
+
+const USAGE: &str = "usage: terminalGraphics [options] [model.obj|model.stl|model.ply]
+    --light x,y,z                       direction the light shines in
+    --intensity value                   brightness of the light
+    --shading flat|gouraud|phong        smooth shading makes curved models look round, flat by default
+    --specular value                    strength (0-1) of the highlights, 0 by default
+    --shininess value                   the higher, the smaller the highlights, 32 by default
+    --ambient value                     brightness (0-1) of the unlit side, 0 by default
+                                        an obj's mtllib materials replace these four per face
+    --output auto|ascii|color|halfblock|braille|sixel|kitty
+                                        auto picks kitty or sixel when the terminal supports it,
+                                        colors otherwise and ascii where TERM has none
+    --ramp dark|light|<glyphs>          glyphs for ascii output, darkest first
+    --colors truecolor|256|16           defaults to what COLORTERM and TERM report
+    --dither none|ordered|floyd-steinberg
+    --threshold value                   luminance (0-1) that raises a braille dot
+    --aspect value                      width / height of a pixel, 0.5 for ascii by default
+    --diff                              only redraw cells that changed since the last frame
+    --spin degrees                      rotation per second, 1 by default
+    --axis x,y,z                        axis the model spins around, 0,1,0 by default
+    --tilt x,y,z                        starting rotation in degrees around each axis
+    --tilt-order xyz|xzy|yxz|yzx|zxy|zyx
+                                        order the tilt rotations are applied in";
+
+///parses a vector like 1,-1,0.5
+fn parse_vector(value: &str) -> Result<Vector3, String> {
+    let components: Vec<f64> = match value.split(',').map(|c| c.trim().parse()).collect() {
+        Ok(components) => components,
+        Err(_) => return Err(format!("'{}' is not a vector like 1,-1,1", value)),
+    };
+    if components.len() != 3 {
+        return Err(format!("'{}' is not a vector like 1,-1,1", value));
+    }
+    Ok(Vector3 {
+        x: components[0],
+        y: components[1],
+        z: components[2],
+    })
+}
+
+///parses a number between 0 and 1
+fn parse_fraction(value: &str) -> Result<f64, String> {
+    match value.parse() {
+        Ok(fraction) if (0. ..=1.).contains(&fraction) => Ok(fraction),
+        _ => Err(format!("'{}' is not a number between 0 and 1", value)),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        model: None,
+        light: shading::new_light(),
+        shading: shading::Shading::Flat,
+        material: shading::new_material(),
+        render: render::Settings {
+            backend: render::Backend::Auto,
+            ramp: render::ascii::Ramp::dark(),
+            dither: render::palette::Dither::None,
+            colors: render::palette::ColorMode::detect(),
+            threshold: 0.5,
         },
-        row_2: Vector3 {
+        pixel_aspect: None,
+        diff: false,
+        spin: 1.,
+        spin_axis: Vector3 {
             x: 0.,
             y: 1.,
             z: 0.,
         },
-        row_3: Vector3 {
-            x: -0.0975,
+        tilt: Vector3 {
+            x: 0.,
             y: 0.,
-            z: 0.95,
+            z: 0.,
         },
+        tilt_order: EulerOrder::Xyz,
     };
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| match args.next() {
+            Some(value) => Ok(value.clone()),
+            None => Err(format!("{} needs a value", name)),
+        };
+        match arg.as_str() {
+            "--light" => options.light.direction = parse_vector(&value(arg)?)?,
+            "--intensity" => {
+                let value = value(arg)?;
+                options.light.intensity = match value.parse() {
+                    Ok(intensity) => intensity,
+                    Err(_) => return Err(format!("'{}' is not a number", value)),
+                };
+            }
+            "--shading" => {
+                options.shading = match value(arg)?.as_str() {
+                    "flat" => shading::Shading::Flat,
+                    "gouraud" => shading::Shading::Gouraud,
+                    "phong" => shading::Shading::Phong,
+                    other => return Err(format!("unknown shading '{}'", other)),
+                };
+            }
+            "--specular" => {
+                let strength = parse_fraction(&value(arg)?)?;
+                options.material.specular = [(strength * 255.).round() as u8; 3];
+            }
+            "--shininess" => {
+                let value = value(arg)?;
+                options.material.shininess = match value.parse() {
+                    Ok(shininess) if shininess > 0. => shininess,
+                    _ => return Err(format!("'{}' is not a positive number", value)),
+                };
+            }
+            "--ambient" => options.material.ambient = parse_fraction(&value(arg)?)?,
+            "--ramp" => {
+                options.render.ramp = match value(arg)?.as_str() {
+                    "dark" => render::ascii::Ramp::dark(),
+                    "light" => render::ascii::Ramp::light(),
+                    glyphs => render::ascii::Ramp::new(glyphs)?,
+                };
+            }
+            "--output" => {
+                options.render.backend = match value(arg)?.as_str() {
+                    "auto" => render::Backend::Auto,
+                    "ascii" => render::Backend::Ascii,
+                    "color" => render::Backend::Color,
+                    "halfblock" => render::Backend::HalfBlock,
+                    "braille" => render::Backend::Braille,
+                    "sixel" => render::Backend::Sixel,
+                    "kitty" => render::Backend::Kitty,
+                    other => return Err(format!("unknown output '{}'", other)),
+                };
+            }
+            "--colors" => {
+                options.render.colors = match value(arg)?.as_str() {
+                    "truecolor" => render::palette::ColorMode::TrueColor,
+                    "256" => render::palette::ColorMode::Palette256,
+                    "16" => render::palette::ColorMode::Palette16,
+                    other => return Err(format!("unknown color mode '{}'", other)),
+                };
+            }
+            "--threshold" => options.render.threshold = parse_fraction(&value(arg)?)?,
+            "--aspect" => {
+                let value = value(arg)?;
+                options.pixel_aspect = match value.parse() {
+                    Ok(aspect) if aspect > 0. => Some(aspect),
+                    _ => return Err(format!("'{}' is not a positive number", value)),
+                };
+            }
+            "--dither" => {
+                options.render.dither = match value(arg)?.as_str() {
+                    "none" => render::palette::Dither::None,
+                    "ordered" => render::palette::Dither::Ordered,
+                    "floyd-steinberg" => render::palette::Dither::FloydSteinberg,
+                    other => return Err(format!("unknown dither '{}'", other)),
+                };
+            }
+            "--diff" => options.diff = true,
+            "--spin" => {
+                let value = value(arg)?;
+                options.spin = match value.parse() {
+                    Ok(spin) => spin,
+                    Err(_) => return Err(format!("'{}' is not a number", value)),
+                };
+            }
+            "--axis" => {
+                let axis = parse_vector(&value(arg)?)?;
+                if axis.x == 0. && axis.y == 0. && axis.z == 0. {
+                    return Err("the spin axis needs a direction".to_owned());
+                }
+                options.spin_axis = axis;
+            }
+            "--tilt" => options.tilt = parse_vector(&value(arg)?)?,
+            "--tilt-order" => {
+                options.tilt_order = match value(arg)?.as_str() {
+                    "xyz" => EulerOrder::Xyz,
+                    "xzy" => EulerOrder::Xzy,
+                    "yxz" => EulerOrder::Yxz,
+                    "yzx" => EulerOrder::Yzx,
+                    "zxy" => EulerOrder::Zxy,
+                    "zyx" => EulerOrder::Zyx,
+                    other => return Err(format!("unknown rotation order '{}'", other)),
+                };
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => options.model = Some(arg.clone()),
+        }
+    }
+    Ok(options)
+}
 
-    loop {
-        let vec_2d: Vec<Vec<[u8; 3]>> = camera::raycasting(
-            main_camera.corners.top_left,
-            main_camera.corners.top_right,
-            main_camera.corners.bottom_left,
-            main_camera.corners.bottom_right,
-            main_camera,
-            obj.clone(),
-        );
-        draw(&vec_2d);
-        std::thread::sleep(time::Duration::from_millis(1000));
-        obj = objects::rotate(obj, one_degree_transform.clone());
+///pixels that fit the terminal, or the old fixed 100x100 when stdout is no terminal
+fn frame_resolution(backend: render::Backend) -> (usize, usize) {
+    match terminal::size() {
+        Some(size) => backend.resolution(size),
+        None => (100, 100),
     }
 }
 
-fn draw(vec_2d: &Vec<Vec<[u8; 3]>>) {
-    let mut string = "".to_owned();
-    for col in vec_2d {
-        for cell in col {
-            if *cell == [0, 0, 0] {
-                string += "...";
-            } else {
-                string += "XXX";
+///creates a single 'snapshot' buffer of chars
+fn test_snapshot(mut obj: objects::Object, options: &Options) {
+    let mut main_camera = camera::new_camera();
+    main_camera.pixel_aspect = options
+        .pixel_aspect
+        .unwrap_or(options.render.backend.pixel_aspect());
+    obj = objects::rotate(
+        obj,
+        Quaternion::from(TransformMatrix::from_euler(
+            options.tilt.x.to_radians(),
+            options.tilt.y.to_radians(),
+            options.tilt.z.to_radians(),
+            options.tilt_order,
+        )),
+    );
+
+    terminal::watch_resize();
+    terminal::watch_interrupt();
+    let mut presenter = presenter::Presenter::new(std::io::stdout(), options.diff);
+    let mut resolution = frame_resolution(options.render.backend);
+    let mut last_frame = time::Instant::now();
+    while !terminal::interrupted() {
+        if terminal::take_resized() {
+            resolution = frame_resolution(options.render.backend);
+            presenter.clear();
+        }
+        obj = objects::update(obj);
+        let vec_2d: Vec<Vec<[u8; 3]>> = camera::raycasting(
+            main_camera,
+            &obj,
+            options.light,
+            options.shading,
+            resolution.0,
+            resolution.1,
+        );
+        presenter.present(&vec_2d, &options.render);
+        //sleep in slices so a resize is drawn and Ctrl-C is handled right away
+        for _ in 0..FRAME_DELAY_MS / RESIZE_POLL_MS {
+            if terminal::interrupted() {
+                break;
             }
+            if terminal::take_resized() {
+                resolution = frame_resolution(options.render.backend);
+                presenter.clear();
+                break;
+            }
+            std::thread::sleep(time::Duration::from_millis(RESIZE_POLL_MS));
+        }
+        //frames cut short by a resize turn the model less, so it keeps spinning at the same speed
+        let elapsed = last_frame.elapsed().as_secs_f64() * 1000. / FRAME_DELAY_MS as f64;
+        last_frame = time::Instant::now();
+        //built from the whole angle, a turn of more than half a circle keeps its direction
+        if let Some(turn) = TransformMatrix::from_axis_angle(
+            options.spin_axis,
+            (options.spin * elapsed).to_radians(),
+        ) {
+            obj = objects::rotate(obj, Quaternion::from(turn));
         }
-        string += "\n";
     }
-    print!("{}", string);
 }
 
 fn main() {
-    test_snapshot();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+    let obj = match &options.model {
+        Some(path) => match loaders::load(Path::new(&path)) {
+            Ok(mesh) => {
+                //the options' material stays the default for faces without a known material
+                let materials =
+                    match loaders::load_materials(Path::new(&path), &mesh, options.material) {
+                        Ok(materials) => materials,
+                        Err(err) => {
+                            eprintln!(
+                                "{}: materials of {:?} not loaded, {}",
+                                path, mesh.material_libraries, err
+                            );
+                            vec![]
+                        }
+                    };
+                let obj = objects::set_material(
+                    objects::fit_into(objects::new_object(mesh), MODEL_RADIUS),
+                    0,
+                    options.material,
+                );
+                materials.into_iter().fold(obj, |obj, (id, material)| {
+                    objects::set_material(obj, id, material)
+                })
+            }
+            Err(err) => {
+                eprintln!("{}: {}", path, err);
+                std::process::exit(1);
+            }
+        },
+        None => objects::set_material(objects::new_test_obj(), 0, options.material),
+    };
+    options.render.backend = options.render.backend.resolve();
+    test_snapshot(obj, &options);
 }
 
 #[cfg(test)]
@@ -68,32 +344,165 @@ mod tests {
     fn test_draw_1() {
         let mut obj: objects::Object = objects::new_test_obj();
         let main_camera = camera::new_camera();
-        let one_degree_transform = geometry::TransformMatrix {
-            row_1: Vector3 {
-                x: 0.95,
-                y: 0.,
-                z: 0.0975,
-            },
-            row_2: Vector3 {
-                x: 0.,
-                y: 1.,
-                z: 0.,
-            },
-            row_3: Vector3 {
-                x: -0.0975,
-                y: 0.,
-                z: 0.95,
-            },
-        };
-        obj = objects::rotate(obj.clone(), one_degree_transform.clone());
+        let one_degree_transform = TransformMatrix::rotation_y(1f64.to_radians());
+        obj = objects::update(objects::rotate(
+            obj.clone(),
+            Quaternion::from(one_degree_transform.clone()),
+        ));
         let vec_2d: Vec<Vec<[u8; 3]>> = camera::raycasting(
-            main_camera.corners.top_left,
-            main_camera.corners.top_right,
-            main_camera.corners.bottom_left,
-            main_camera.corners.bottom_right,
             main_camera,
-            obj.clone(),
+            &obj,
+            shading::new_light(),
+            shading::Shading::Phong,
+            100,
+            100,
+        );
+        let mut settings = parse_args(&[]).unwrap().render;
+        //every backend draws twice into a buffer, the second frame goes through the diff
+        let draw = |settings: &render::Settings| {
+            let mut out: Vec<u8> = vec![];
+            {
+                let mut presenter = presenter::Presenter::new(&mut out, true);
+                presenter.present(&vec_2d, settings);
+                presenter.present(&vec_2d, settings);
+            }
+            let text = String::from_utf8(out).unwrap();
+            let enter = "\x1b[?1049h\x1b[?25l\x1b[2J";
+            let leave = "\x1b[?25h\x1b[?1049l";
+            assert!(text.starts_with(enter) && text.ends_with(leave));
+            assert!(text.len() > enter.len() + leave.len() + 100);
+        };
+        draw(&settings);
+        settings.backend = render::Backend::Color;
+        settings.colors = render::palette::ColorMode::Palette256;
+        settings.dither = render::palette::Dither::FloydSteinberg;
+        draw(&settings);
+        settings.backend = render::Backend::HalfBlock;
+        settings.colors = render::palette::ColorMode::TrueColor;
+        draw(&settings);
+        settings.backend = render::Backend::Braille;
+        settings.dither = render::palette::Dither::Ordered;
+        draw(&settings);
+        settings.backend = render::Backend::Sixel;
+        draw(&settings);
+        settings.backend = render::Backend::Kitty;
+        draw(&settings);
+    }
+    #[test]
+    fn test_parse_args_1() {
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let options = parse_args(&args(&[
+            "--light",
+            "0,-1,0.5",
+            "--intensity",
+            "0.7",
+            "a.obj",
+        ]))
+        .unwrap();
+        assert_eq!(options.model, Some("a.obj".to_owned()));
+        assert_eq!(
+            options.light.direction,
+            Vector3 {
+                x: 0.,
+                y: -1.,
+                z: 0.5
+            }
+        );
+        assert_eq!(options.light.intensity, 0.7);
+        assert!(parse_args(&args(&[])).unwrap().model.is_none());
+        assert!(parse_args(&args(&["--light", "1,2"])).is_err());
+        assert!(parse_args(&args(&["--intensity"])).is_err());
+        assert!(parse_args(&args(&["--bogus"])).is_err());
+        assert_eq!(
+            parse_args(&args(&["--ramp", "light"])).unwrap().render.ramp,
+            render::ascii::Ramp::light()
+        );
+        assert_eq!(
+            parse_args(&args(&["--ramp", " .o"])).unwrap().render.ramp,
+            render::ascii::Ramp::new(" .o").unwrap()
+        );
+        assert!(parse_args(&args(&["--ramp", "x"])).is_err());
+        let options = parse_args(&args(&[
+            "--output",
+            "halfblock",
+            "--colors",
+            "16",
+            "--dither",
+            "ordered",
+        ]))
+        .unwrap();
+        assert_eq!(options.render.backend, render::Backend::HalfBlock);
+        assert_eq!(options.render.colors, render::palette::ColorMode::Palette16);
+        assert_eq!(options.render.dither, render::palette::Dither::Ordered);
+        assert!(parse_args(&args(&["--output", "paper"])).is_err());
+        assert!(parse_args(&args(&["--dither", "noise"])).is_err());
+        assert!(parse_args(&args(&["--colors", "8"])).is_err());
+        assert_eq!(
+            parse_args(&args(&["--aspect", "0.45"]))
+                .unwrap()
+                .pixel_aspect,
+            Some(0.45)
+        );
+        assert!(parse_args(&args(&["--aspect", "-1"])).is_err());
+        assert!(!parse_args(&args(&[])).unwrap().diff);
+        let options = parse_args(&args(&[
+            "--spin",
+            "-2.5",
+            "--axis",
+            "1,0,0",
+            "--tilt",
+            "30,0,90",
+            "--tilt-order",
+            "zyx",
+        ]))
+        .unwrap();
+        assert_eq!(options.spin, -2.5);
+        assert_eq!(options.spin_axis.x, 1.);
+        assert_eq!(options.tilt.z, 90.);
+        assert_eq!(options.tilt_order, EulerOrder::Zyx);
+        assert!(parse_args(&args(&["--axis", "0,0,0"])).is_err());
+        assert!(parse_args(&args(&["--tilt-order", "xxy"])).is_err());
+        assert!(parse_args(&args(&["--diff"])).unwrap().diff);
+        assert_eq!(
+            parse_args(&args(&[])).unwrap().shading,
+            shading::Shading::Flat
+        );
+        assert_eq!(
+            parse_args(&args(&["--shading", "gouraud"]))
+                .unwrap()
+                .shading,
+            shading::Shading::Gouraud
+        );
+        assert!(parse_args(&args(&["--shading", "smooth"])).is_err());
+        let options = parse_args(&args(&[
+            "--specular",
+            "1",
+            "--shininess",
+            "8",
+            "--ambient",
+            "0.1",
+        ]))
+        .unwrap();
+        assert_eq!(options.material.specular, [255, 255, 255]);
+        assert_eq!(options.material.shininess, 8.);
+        assert_eq!(options.material.ambient, 0.1);
+        assert!(parse_args(&args(&["--specular", "2"])).is_err());
+        assert!(parse_args(&args(&["--shininess", "0"])).is_err());
+        assert!(parse_args(&args(&["--ambient", "dim"])).is_err());
+        let options = parse_args(&args(&["--output", "braille", "--threshold", "0.2"])).unwrap();
+        assert_eq!(options.render.backend, render::Backend::Braille);
+        assert_eq!(options.render.threshold, 0.2);
+        assert!(parse_args(&args(&["--threshold", "5"])).is_err());
+        assert_eq!(
+            parse_args(&args(&[])).unwrap().render.backend,
+            render::Backend::Auto
+        );
+        assert_eq!(
+            parse_args(&args(&["--output", "sixel"]))
+                .unwrap()
+                .render
+                .backend,
+            render::Backend::Sixel
         );
-        draw(&vec_2d);
     }
 }
@@ -1,40 +1,29 @@
-use crate::geometry::{TransformMatrix, Vector3};
+use crate::geometry::{Matrix4, Mesh, Quaternion, Transform, TransformMatrix, Vector3};
+use crate::shading::{self, Material};
 use std::vec;
 pub(crate) type Point = Vector3;
 
-const _UNIT_MATRIX: TransformMatrix = TransformMatrix {
-    row_1: Vector3 {
-        x: 1.,
-        y: 0.,
-        z: 0.,
-    },
-    row_2: Vector3 {
-        x: 0.,
-        y: 1.,
-        z: 0.,
-    },
-    row_3: Vector3 {
-        x: 0.,
-        y: 0.,
-        z: 1.,
-    },
-};
-
 #[derive(Clone)]
 pub struct Object {
-    pub origin: Point,
-    pub vertices: Vec<Vector3>,
+    ///rest pose in object space, never changed after loading
+    mesh: Mesh,
+    ///`mesh.positions` in world space, brought up to date by `update`
     pub transform_vertices: Vec<Vector3>,
-    pub polygons: Vec<[usize; 3]>,
-    pub transform_matrix: TransformMatrix,
-    pub _tranlation_vector: Vector3,
+    ///places the object in the world, only changed through `set_transform` so `dirty` can't be missed
+    transform: Transform,
+    ///indexed by `mesh.material_ids`, the first one is the object's own and stands in for missing ones
+    pub materials: Vec<Material>,
+    ///turns vertex normals into world space, brought up to date by `update`
+    normal_matrix: TransformMatrix,
+    ///`transform` changed since `transform_vertices` were computed
+    dirty: bool,
 }
 impl std::fmt::Display for Object {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         write!(
             f,
-            "Object:\n        Origin: {}\n        Vertices: {:?}\n        Polygon list: {:?}\n\n",
-            self.origin, self.vertices, self.polygons
+            "Object:\n        {}\n        Outdated: {}\n        Materials: {:?}\n        {}",
+            self.transform, self.dirty, self.materials, self.mesh
         )
     }
 }
@@ -63,47 +52,431 @@ pub fn new_test_obj() -> Object {
             z: -50.,
         },
     ];
-    let polygons = vec![[0, 1, 2], [0, 1, 3], [2, 3, 0], [1, 2, 3]];
-    let origin = Point {
-        x: 0.,
-        y: 0.,
-        z: 100.,
-    };
+    //every face winds counterclockwise seen from outside, so the filled normals point outward
+    let polygons = vec![[0, 2, 1], [0, 1, 3], [2, 0, 3], [1, 2, 3]];
+
+    new_object(Mesh::new(vertices, polygons))
+}
 
-    Object {
-        origin,
-        vertices: vertices.clone(),
-        transform_vertices: vertices,
-        polygons,
-        transform_matrix: _UNIT_MATRIX.clone(),
-        _tranlation_vector: Vector3 {
+///places a loaded mesh where the test object sits, vertices the file gave no normal get a computed one;
+///meshes with face normals (stl) are left without, smoothing would round off their hard edges
+pub fn new_object(mut mesh: Mesh) -> Object {
+    if mesh.face_normals.is_none() {
+        mesh.fill_normals();
+    }
+    let obj = Object {
+        transform_vertices: mesh.positions.clone(),
+        mesh,
+        transform: Transform::identity(),
+        materials: vec![shading::new_material()],
+        normal_matrix: TransformMatrix::identity(),
+        dirty: false,
+    };
+    update(translate(
+        obj,
+        Point {
             x: 0.,
             y: 0.,
-            z: 0.,
+            z: 100.,
         },
+    ))
+}
+
+///turns and scales the object around the center of its vertices, the farthest one ends up `radius` away
+pub fn fit_into(obj: Object, radius: f64) -> Object {
+    if obj.mesh.positions.is_empty() {
+        return obj;
+    }
+    let mut min = obj.mesh.positions[0];
+    let mut max = obj.mesh.positions[0];
+    for vertex in &obj.mesh.positions {
+        for axis in 0..3 {
+            min[axis] = min[axis].min(vertex[axis]);
+            max[axis] = max[axis].max(vertex[axis]);
+        }
+    }
+    let center = (min + max) * 0.5;
+
+    let mut farthest: f64 = 0.;
+    for vertex in &obj.mesh.positions {
+        farthest = farthest.max((*vertex - center).length());
+    }
+    let mut transform = obj.transform;
+    transform.pivot = center;
+    let factor = if farthest > 0. { radius / farthest } else { 1. };
+    scale(set_transform(obj, transform), factor)
+}
+
+///average color of the polygon's corners, white if the model has no colors
+pub fn polygon_color(obj: &Object, polygon_index: usize) -> [u8; 3] {
+    match &obj.mesh.colors {
+        Some(colors) => {
+            let mut sum = [0u32; 3];
+            for vertex_index in obj.mesh.indices[polygon_index] {
+                for channel in 0..3 {
+                    sum[channel] += colors[vertex_index][channel] as u32;
+                }
+            }
+            [(sum[0] / 3) as u8, (sum[1] / 3) as u8, (sum[2] / 3) as u8]
+        }
+        None => [255, 255, 255],
     }
 }
 
-pub fn rotate(mut obj: Object, transform_matrix: TransformMatrix) -> Object {
-    let mut new_vertices: Vec<Vector3> = vec![];
-    for vertex in obj.vertices.clone() {
-        new_vertices.push(transform_matrix.clone() * vertex);
+///the material of the polygon, the object's own if the mesh assigns none or one that is not set
+pub fn polygon_material(obj: &Object, polygon_index: usize) -> Material {
+    let material_id = match &obj.mesh.material_ids {
+        Some(material_ids) => material_ids[polygon_index],
+        None => 0,
+    };
+    *obj.materials.get(material_id).unwrap_or(&obj.materials[0])
+}
+
+///sets material `material_id`, 0 is the object's own one, polygons with that id in `mesh.material_ids` use it
+pub fn set_material(mut obj: Object, material_id: usize, material: Material) -> Object {
+    if material_id >= obj.materials.len() {
+        let fallback = obj.materials[0];
+        obj.materials.resize(material_id + 1, fallback);
     }
+    obj.materials[material_id] = material;
+    obj
+}
+
+///the vertex normals at the polygon's corners, in world space; `None` if a corner has none
+pub fn corner_normals(obj: &Object, polygon_index: usize) -> Option<[Vector3; 3]> {
+    let normals = obj.mesh.normals.as_ref()?;
+    let [a, b, c] = obj.mesh.indices[polygon_index];
+    Some([normals[a]?, normals[b]?, normals[c]?].map(|normal| obj.normal_matrix.clone() * normal))
+}
+
+///spins the object around its own position, `rotation` is in world space
+pub fn rotate(obj: Object, rotation: Quaternion) -> Object {
+    let center = position(&obj);
+    orbit(obj, center, rotation)
+}
+
+///swings the object around `center`, it turns with the swing like the moon does around the earth
+pub fn orbit(obj: Object, center: Vector3, rotation: Quaternion) -> Object {
+    let swing = Matrix4::translation(center)
+        * Matrix4::from(TransformMatrix::from(rotation))
+        * Matrix4::translation(-center);
+    let mut transform = obj.transform;
+    transform.translation =
+        (swing * Matrix4::translation(transform.translation)).translation_part();
+    //normalizing keeps many small turns from shearing or shrinking the model
+    transform.rotation = (rotation * transform.rotation).normalize();
+    set_transform(obj, transform)
+}
 
-    let new_transform_matrix = transform_matrix * obj.transform_matrix;
+pub fn translate(obj: Object, offset: Vector3) -> Object {
+    let mut transform = obj.transform;
+    transform.translation += offset;
+    set_transform(obj, transform)
+}
+
+///grows or shrinks the object around its own position
+pub fn scale(obj: Object, factor: f64) -> Object {
+    let mut transform = obj.transform;
+    transform.scale = transform.scale * factor;
+    set_transform(obj, transform)
+}
+
+///where the object is in the world
+pub fn position(obj: &Object) -> Vector3 {
+    transform(obj).translation
+}
+
+///how the object is placed in the world
+pub fn transform(obj: &Object) -> Transform {
+    obj.transform
+}
 
-    for i in 0..obj.transform_vertices.len() {
-        let vertex = obj.transform_vertices[i];
-        obj.transform_vertices[i][0] = new_transform_matrix.row_1.x * vertex[0]
-            + new_transform_matrix.row_1.y * vertex[1]
-            + new_transform_matrix.row_1.z * vertex[2];
-        obj.transform_vertices[i][1] = new_transform_matrix.row_2.x * vertex[0]
-            + new_transform_matrix.row_2.y * vertex[1]
-            + new_transform_matrix.row_2.z * vertex[2];
-        obj.transform_vertices[i][2] = new_transform_matrix.row_3.x * vertex[0]
-            + new_transform_matrix.row_3.y * vertex[1]
-            + new_transform_matrix.row_3.z * vertex[2];
+///replaces the whole transform, the world space vertices follow on the next `update`
+pub fn set_transform(mut obj: Object, transform: Transform) -> Object {
+    obj.transform = transform;
+    obj.dirty = true;
+    obj
+}
+
+///the rest pose as loaded, in object space
+pub fn mesh(obj: &Object) -> &Mesh {
+    &obj.mesh
+}
+
+///derives the world space vertices from the rest pose, only if the transform changed since the last time
+pub fn update(mut obj: Object) -> Object {
+    if !obj.dirty {
+        return obj;
     }
-    obj.transform_matrix = new_transform_matrix;
+    let model_matrix = obj.transform.matrix();
+    obj.transform_vertices = obj
+        .mesh
+        .positions
+        .iter()
+        .map(|vertex| model_matrix.transform_point(*vertex))
+        .collect();
+    let scale = obj.transform.scale;
+    let linear = TransformMatrix::from(obj.transform.rotation)
+        * TransformMatrix::scale(scale.x, scale.y, scale.z);
+    //a scale of 0 flattens the object, its normals don't matter much then
+    obj.normal_matrix = linear.normal_matrix().unwrap_or(linear);
+    obj.dirty = false;
     obj
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn close(a: Vector3, b: Vector3) -> bool {
+        a.approx_eq(b, 1e-9)
+    }
+    fn quarter_turn() -> Quaternion {
+        Quaternion::from(TransformMatrix::rotation_y(std::f64::consts::FRAC_PI_2))
+    }
+    #[test]
+    fn test_transform_moves_vertices() {
+        let obj = new_test_obj();
+        assert!(close(
+            position(&obj),
+            Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 100.
+            }
+        ));
+        assert!(close(
+            obj.transform_vertices[2],
+            obj.mesh.positions[2] + position(&obj)
+        ));
+
+        let offset = Vector3 {
+            x: 5.,
+            y: -5.,
+            z: 0.,
+        };
+        let moved = update(translate(scale(obj.clone(), 2.), offset));
+        assert!(close(position(&moved), position(&obj) + offset));
+        assert!(close(
+            moved.transform_vertices[2],
+            obj.mesh.positions[2] * 2. + position(&obj) + offset
+        ));
+    }
+    #[test]
+    fn test_rotate_and_orbit() {
+        //spinning keeps the object in place
+        let spun = rotate(new_test_obj(), quarter_turn());
+        assert!(close(position(&spun), position(&new_test_obj())));
+        //orbiting around the world origin swings it from the front to the side
+        let orbited = orbit(
+            new_test_obj(),
+            Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 0.,
+            },
+            quarter_turn(),
+        );
+        assert!(close(
+            position(&orbited),
+            Vector3 {
+                x: 100.,
+                y: 0.,
+                z: 0.
+            }
+        ));
+        //four quarter turns bring every vertex back, without growing or shrinking
+        let mut obj = scale(new_test_obj(), 1.5);
+        let start = update(obj.clone()).transform_vertices;
+        for _ in 0..4 {
+            obj = rotate(obj, quarter_turn());
+        }
+        for (vertex, expected) in update(obj).transform_vertices.iter().zip(start) {
+            assert!(close(*vertex, expected));
+        }
+    }
+    #[test]
+    fn test_rotate_does_not_compound() {
+        //every step turns by the same angle, no matter how often the object was turned before
+        let step = Quaternion::from(TransformMatrix::rotation_y(1f64.to_radians()));
+        let mut obj = new_test_obj();
+        for _ in 0..90 {
+            obj = rotate(obj, step);
+        }
+        let turned = update(obj);
+        let expected = update(rotate(new_test_obj(), quarter_turn()));
+        for (vertex, expected) in turned
+            .transform_vertices
+            .iter()
+            .zip(expected.transform_vertices)
+        {
+            assert!(close(*vertex, expected));
+        }
+        assert!(turned.mesh == new_test_obj().mesh);
+    }
+    #[test]
+    fn test_update_only_when_dirty() {
+        let mut obj = rotate(new_test_obj(), quarter_turn());
+        assert!(obj.dirty);
+        //stale until updated
+        assert!(obj.transform_vertices == new_test_obj().transform_vertices);
+        obj = update(obj);
+        assert!(!obj.dirty);
+        assert!(obj.transform_vertices != new_test_obj().transform_vertices);
+        obj.transform_vertices.clear();
+        obj = update(obj);
+        assert!(obj.transform_vertices.is_empty());
+        //replacing the transform as a whole marks the object too
+        let mut moved = transform(&obj);
+        moved.translation.y += 1.;
+        obj = set_transform(obj, moved);
+        assert!(obj.dirty);
+        assert!(update(obj).transform_vertices.len() == 4);
+    }
+    #[test]
+    fn test_fit_into_keeps_rest_pose() {
+        let obj = new_object(Mesh::new(
+            vec![
+                Vector3 {
+                    x: 10.,
+                    y: 10.,
+                    z: 10.,
+                },
+                Vector3 {
+                    x: 12.,
+                    y: 10.,
+                    z: 10.,
+                },
+            ],
+            vec![],
+        ));
+        let fitted = update(fit_into(obj.clone(), 5.));
+        assert!(fitted.mesh == obj.mesh);
+        //centered on the position, the farthest vertex 5 away
+        assert!(close(
+            fitted.transform_vertices[1],
+            position(&obj)
+                + Vector3 {
+                    x: 5.,
+                    y: 0.,
+                    z: 0.
+                }
+        ));
+    }
+    #[test]
+    fn test_corner_normals_follow_transform() {
+        let triangle = Mesh::new(
+            vec![
+                Vector3 {
+                    x: 0.,
+                    y: 0.,
+                    z: 0.,
+                },
+                Vector3 {
+                    x: 1.,
+                    y: 0.,
+                    z: 0.,
+                },
+                Vector3 {
+                    x: 0.,
+                    y: 1.,
+                    z: 0.,
+                },
+            ],
+            vec![[0, 1, 2]],
+        );
+        let obj = new_object(triangle);
+        for normal in corner_normals(&obj, 0).unwrap() {
+            assert!(close(
+                normal,
+                Vector3 {
+                    x: 0.,
+                    y: 0.,
+                    z: 1.
+                }
+            ));
+        }
+        let turned = update(rotate(obj.clone(), quarter_turn()));
+        for normal in corner_normals(&turned, 0).unwrap() {
+            assert!(close(
+                normal,
+                Vector3 {
+                    x: 1.,
+                    y: 0.,
+                    z: 0.
+                }
+            ));
+        }
+        //stretching sideways makes a 45 degree slope flatter, its normal tilts towards up
+        let mut slope = obj;
+        slope.mesh.normals = Some(vec![
+            Some(Vector3 {
+                x: 1.,
+                y: 1.,
+                z: 0.,
+            });
+            3
+        ]);
+        let mut stretch = transform(&slope);
+        stretch.scale.x = 3.;
+        let stretched = update(set_transform(slope, stretch));
+        for normal in corner_normals(&stretched, 0).unwrap() {
+            assert!(close(
+                normal,
+                Vector3 {
+                    x: 1. / 3.,
+                    y: 1.,
+                    z: 0.
+                }
+            ));
+        }
+        //face normals mean hard edges, those meshes stay flat
+        let mut faceted = mesh(&turned).clone();
+        faceted.normals = None;
+        faceted.face_normals = Some(vec![
+            Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 1.,
+            };
+            1
+        ]);
+        assert!(corner_normals(&new_object(faceted), 0).is_none());
+    }
+    #[test]
+    fn test_test_obj_normals_point_outward() {
+        let mesh = mesh(&new_test_obj()).clone();
+        let center = mesh.positions.iter().fold(
+            Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 0.,
+            },
+            |sum, position| sum + *position,
+        ) / mesh.positions.len() as f64;
+        let normals = mesh.normals.unwrap();
+        for (position, normal) in mesh.positions.iter().zip(normals) {
+            let outward = (*position - center).normalize();
+            assert!(normal.unwrap().dot(outward) > 0.5);
+        }
+    }
+    #[test]
+    fn test_materials_per_object_and_face() {
+        let mut obj = new_test_obj();
+        let red = Material {
+            diffuse: [255, 0, 0],
+            ..shading::new_material()
+        };
+        let blue = Material {
+            diffuse: [0, 0, 255],
+            ..red
+        };
+        obj = set_material(obj, 0, red);
+        assert!((0..4).all(|i| polygon_material(&obj, i) == red));
+        //faces pick their own material, ids without one fall back to the object's
+        obj.mesh.material_ids = Some(vec![0, 2, 1, 5]);
+        obj = set_material(obj, 2, blue);
+        assert!(polygon_material(&obj, 1) == blue);
+        assert!(polygon_material(&obj, 2) == red);
+        assert!(polygon_material(&obj, 3) == red);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::render::{self, Cell};
+use std::io::Write;
+
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CURSOR_HOME: &str = "\x1b[H";
+
+///draws frames over each other on the alternate screen, the terminal is restored when dropped
+pub struct Presenter<W: Write> {
+    out: W,
+    ///only rewrite the cells that changed since the last frame
+    diff: bool,
+    ///the cells on screen, `None` after a clear or when the backend draws pixels
+    previous: Option<Vec<Vec<Cell>>>,
+}
+impl<W: Write> Presenter<W> {
+    pub fn new(mut out: W, diff: bool) -> Presenter<W> {
+        let _ = write!(
+            out,
+            "{}{}{}",
+            ENTER_ALTERNATE_SCREEN, HIDE_CURSOR, CLEAR_SCREEN
+        );
+        let _ = out.flush();
+        Presenter {
+            out,
+            diff,
+            previous: None,
+        }
+    }
+    ///wipes the screen before the next frame, needed when the frame size changes
+    pub fn clear(&mut self) {
+        self.previous = None;
+        let _ = write!(self.out, "{}", CLEAR_SCREEN);
+    }
+    pub fn present(&mut self, frame: &[Vec<[u8; 3]>], settings: &render::Settings) {
+        let output = match render::cells(frame, settings) {
+            Some(grid) => {
+                let output = match &self.previous {
+                    Some(previous) if self.diff => render::encode_changes(previous, &grid),
+                    _ => format!("{}{}", CURSOR_HOME, render::encode(&grid)),
+                };
+                self.previous = Some(grid);
+                output
+            }
+            //sixel and kitty images are always sent whole
+            None => format!("{}{}", CURSOR_HOME, render::render(frame, settings)),
+        };
+        let _ = self.out.write_all(output.as_bytes());
+        let _ = self.out.flush();
+    }
+}
+impl<W: Write> Drop for Presenter<W> {
+    fn drop(&mut self) {
+        let _ = write!(self.out, "{}{}", SHOW_CURSOR, LEAVE_ALTERNATE_SCREEN);
+        let _ = self.out.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn settings() -> render::Settings {
+        render::Settings {
+            backend: render::Backend::Ascii,
+            ramp: render::ascii::Ramp::new(" #").unwrap(),
+            dither: render::palette::Dither::None,
+            colors: render::palette::ColorMode::TrueColor,
+            threshold: 0.5,
+        }
+    }
+    #[test]
+    fn test_presenter_restores_terminal() {
+        let mut out: Vec<u8> = vec![];
+        {
+            let mut presenter = Presenter::new(&mut out, false);
+            presenter.present(&[vec![[0, 0, 0], [255, 255, 255]]], &settings());
+            presenter.present(&[vec![[0, 0, 0], [255, 255, 255]]], &settings());
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[?1049h\x1b[?25l\x1b[2J\x1b[H #\n\x1b[H #\n\x1b[?25h\x1b[?1049l"
+        );
+    }
+    #[test]
+    fn test_presenter_diff() {
+        let mut out: Vec<u8> = vec![];
+        {
+            let mut presenter = Presenter::new(&mut out, true);
+            presenter.present(&[vec![[0, 0, 0], [0, 0, 0]]], &settings());
+            presenter.present(&[vec![[0, 0, 0], [255, 255, 255]]], &settings());
+            presenter.present(&[vec![[0, 0, 0], [255, 255, 255]]], &settings());
+            presenter.clear();
+            presenter.present(&[vec![[0, 0, 0], [255, 255, 255]]], &settings());
+        }
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\x1b[?1049h\x1b[?25l\x1b[2J\x1b[H  \n\x1b[1;2H#\x1b[2J\x1b[H #\n\x1b[?25h\x1b[?1049l"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use super::palette::{self, ColorMode, Dither};
+use super::{Cell, Color};
+
+///converts the frame into the colors the terminal can show
+pub fn colors(frame: &[Vec<[u8; 3]>], mode: ColorMode, dither: Dither) -> Vec<Vec<Color>> {
+    match mode {
+        ColorMode::TrueColor => frame
+            .iter()
+            .map(|row| row.iter().map(|pixel| Color::Rgb(*pixel)).collect())
+            .collect(),
+        //the basic 16 are left out, terminals theme them freely
+        ColorMode::Palette256 => palette::quantize(frame, &palette::xterm_256()[16..], dither)
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|index| Color::Indexed(*index as u8 + 16))
+                    .collect()
+            })
+            .collect(),
+        ColorMode::Palette16 => palette::quantize(frame, &palette::ANSI_16, dither)
+            .iter()
+            .map(|row| row.iter().map(|index| Color::Basic(*index as u8)).collect())
+            .collect(),
+    }
+}
+
+///paints every pixel as the background color of one cell
+pub fn render(frame: &[Vec<[u8; 3]>], mode: ColorMode, dither: Dither) -> Vec<Vec<Cell>> {
+    let mut grid: Vec<Vec<Cell>> = vec![];
+    for row in colors(frame, mode, dither) {
+        let mut cells: Vec<Cell> = vec![];
+        for color in row {
+            cells.push(Cell {
+                glyph: ' ',
+                fg: None,
+                bg: Some(color),
+            });
+        }
+        grid.push(cells);
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::encode;
+    use super::*;
+    #[test]
+    fn test_render_truecolor() {
+        let frame = vec![vec![[10, 20, 30], [10, 20, 30], [0, 0, 0]]];
+        assert_eq!(
+            encode(&render(&frame, ColorMode::TrueColor, Dither::None)),
+            "\x1b[48;2;10;20;30m  \x1b[48;2;0;0;0m \x1b[0m\n"
+        );
+    }
+    #[test]
+    fn test_colors_palettes() {
+        let frame = vec![vec![[255, 0, 0], [0, 0, 0], [128, 128, 128]]];
+        assert_eq!(
+            colors(&frame, ColorMode::Palette256, Dither::None),
+            vec![vec![
+                Color::Indexed(196),
+                Color::Indexed(16),
+                Color::Indexed(244)
+            ]]
+        );
+        assert_eq!(
+            colors(&frame, ColorMode::Palette16, Dither::None),
+            vec![vec![Color::Basic(9), Color::Basic(0), Color::Basic(8)]]
+        );
+    }
+}
@@ -0,0 +1,84 @@
+use super::{luminance, Cell};
+
+///glyphs ordered from darkest to brightest
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ramp {
+    glyphs: Vec<char>,
+}
+impl Ramp {
+    ///for light text on a dark background, dense glyphs are bright
+    pub fn dark() -> Ramp {
+        Ramp {
+            glyphs: " .:-=+*#%@".chars().collect(),
+        }
+    }
+    ///for dark text on a light background, dense glyphs are dark
+    pub fn light() -> Ramp {
+        Ramp {
+            glyphs: "@%#*+=-:. ".chars().collect(),
+        }
+    }
+    ///a user supplied ramp, first glyph for black and last glyph for white
+    pub fn new(glyphs: &str) -> Result<Ramp, String> {
+        let glyphs: Vec<char> = glyphs.chars().collect();
+        if glyphs.len() < 2 {
+            return Err("a ramp needs at least 2 glyphs".to_owned());
+        }
+        if glyphs.iter().any(|glyph| glyph.is_control()) {
+            return Err("a ramp can not contain control characters".to_owned());
+        }
+        Ok(Ramp { glyphs })
+    }
+    pub fn glyph(&self, luminance: f64) -> char {
+        let last = self.glyphs.len() - 1;
+        let index = (luminance.clamp(0., 1.) * last as f64).round() as usize;
+        self.glyphs[index]
+    }
+}
+
+///maps every pixel's luminance onto the ramp
+pub fn render(frame: &[Vec<[u8; 3]>], ramp: &Ramp) -> Vec<Vec<Cell>> {
+    let mut grid: Vec<Vec<Cell>> = vec![];
+    for row in frame {
+        let mut cells: Vec<Cell> = vec![];
+        for pixel in row {
+            cells.push(Cell::plain(ramp.glyph(luminance(*pixel))));
+        }
+        grid.push(cells);
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::encode;
+    use super::*;
+    #[test]
+    fn test_ramp_glyph_1() {
+        let ramp = Ramp::dark();
+        assert_eq!(ramp.glyph(0.), ' ');
+        assert_eq!(ramp.glyph(1.), '@');
+        assert_eq!(ramp.glyph(0.5), '+');
+        assert_eq!(ramp.glyph(-3.), ' ');
+        assert_eq!(ramp.glyph(7.), '@');
+        assert_eq!(Ramp::light().glyph(1.), ' ');
+        assert_eq!(Ramp::light().glyph(0.), '@');
+    }
+    #[test]
+    fn test_ramp_new_1() {
+        let ramp = Ramp::new("_o0").unwrap();
+        assert_eq!(ramp.glyph(0.), '_');
+        assert_eq!(ramp.glyph(0.5), 'o');
+        assert_eq!(ramp.glyph(0.9), '0');
+        assert!(Ramp::new("x").is_err());
+        assert!(Ramp::new("a\tb").is_err());
+    }
+    #[test]
+    fn test_render_1() {
+        let frame = vec![
+            vec![[0, 0, 0], [255, 255, 255]],
+            vec![[128, 128, 128], [0, 0, 0]],
+        ];
+        assert_eq!(encode(&render(&frame, &Ramp::dark())), " @\n+ \n");
+    }
+}
@@ -0,0 +1,95 @@
+use super::palette::{self, Dither};
+use super::{luminance, Cell};
+
+///bit of each dot inside a braille cell, indexed by [row][column]
+const DOT_BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+const BRAILLE_BLANK: u32 = 0x2800;
+
+///decides for every pixel whether its dot is raised
+pub fn dots(frame: &[Vec<[u8; 3]>], dither: Dither, threshold: f64) -> Vec<Vec<bool>> {
+    match dither {
+        Dither::None => frame
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|pixel| luminance(*pixel) > threshold)
+                    .collect()
+            })
+            .collect(),
+        //dithering spreads the gray between black and white itself, so no threshold
+        dither => {
+            let gray: Vec<Vec<[u8; 3]>> = frame
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|pixel| [(luminance(*pixel) * 255.).round() as u8; 3])
+                        .collect()
+                })
+                .collect();
+            palette::quantize(&gray, &[[0, 0, 0], [255, 255, 255]], dither)
+                .iter()
+                .map(|row| row.iter().map(|index| *index == 1).collect())
+                .collect()
+        }
+    }
+}
+
+///packs 2x4 pixels into every cell, monochrome
+pub fn render(frame: &[Vec<[u8; 3]>], dither: Dither, threshold: f64) -> Vec<Vec<Cell>> {
+    let dots = dots(frame, dither, threshold);
+    let width = dots.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut grid: Vec<Vec<Cell>> = vec![];
+    for rows in dots.chunks(4) {
+        let mut cells: Vec<Cell> = vec![];
+        for column in 0..width.div_ceil(2) {
+            let mut bits = 0;
+            for (dy, row) in rows.iter().enumerate() {
+                for (dx, bit) in DOT_BITS[dy].iter().enumerate() {
+                    if row.get(column * 2 + dx) == Some(&true) {
+                        bits |= bit;
+                    }
+                }
+            }
+            cells.push(Cell::plain(
+                char::from_u32(BRAILLE_BLANK + bits).unwrap_or(' '),
+            ));
+        }
+        grid.push(cells);
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::encode;
+    use super::*;
+    #[test]
+    fn test_render_braille_1() {
+        let w = [255, 255, 255];
+        let b = [0, 0, 0];
+        //left column full, right column only the bottom dot, then a half cell
+        let frame = vec![
+            vec![w, b, w],
+            vec![w, b, b],
+            vec![w, b, b],
+            vec![w, w, b],
+            vec![b, b, w],
+        ];
+        assert_eq!(
+            encode(&render(&frame, Dither::None, 0.5)),
+            "\u{28c7}\u{2801}\n\u{2800}\u{2801}\n"
+        );
+    }
+    #[test]
+    fn test_dots_threshold_and_dither() {
+        let frame = vec![vec![[100, 100, 100]; 4]; 4];
+        let count = |dots: Vec<Vec<bool>>| dots.iter().flatten().filter(|dot| **dot).count();
+        assert_eq!(count(dots(&frame, Dither::None, 0.5)), 0);
+        assert_eq!(count(dots(&frame, Dither::None, 0.3)), 16);
+        //100/255 is a bit less than 40% gray
+        let ordered = count(dots(&frame, Dither::Ordered, 0.5));
+        assert!((5..=7).contains(&ordered), "{} dots", ordered);
+        let diffused = count(dots(&frame, Dither::FloydSteinberg, 0.5));
+        assert!((5..=7).contains(&diffused), "{} dots", diffused);
+    }
+}
@@ -0,0 +1,50 @@
+use super::ansi;
+use super::palette::{ColorMode, Dither};
+use super::Cell;
+
+///upper half block, the foreground paints the top pixel and the background the bottom one
+const UPPER_HALF: char = '▀';
+
+///packs two rows of pixels into one row of cells, so every pixel is about square
+pub fn render(frame: &[Vec<[u8; 3]>], mode: ColorMode, dither: Dither) -> Vec<Vec<Cell>> {
+    let colors = ansi::colors(frame, mode, dither);
+    let mut grid: Vec<Vec<Cell>> = vec![];
+    for pair in colors.chunks(2) {
+        let mut cells: Vec<Cell> = vec![];
+        for x in 0..pair[0].len() {
+            cells.push(Cell {
+                glyph: UPPER_HALF,
+                fg: Some(pair[0][x]),
+                //an odd last row leaves the bottom half to the terminal background
+                bg: pair.get(1).and_then(|bottom| bottom.get(x).copied()),
+            });
+        }
+        grid.push(cells);
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{encode, Color};
+    use super::*;
+    #[test]
+    fn test_render_halfblock_1() {
+        let frame = vec![
+            vec![[255, 0, 0], [255, 0, 0]],
+            vec![[0, 0, 255], [0, 0, 255]],
+            vec![[0, 255, 0], [0, 0, 0]],
+        ];
+        let grid = render(&frame, ColorMode::TrueColor, Dither::None);
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid[0].len(), 2);
+        assert_eq!(grid[0][1].fg, Some(Color::Rgb([255, 0, 0])));
+        assert_eq!(grid[0][1].bg, Some(Color::Rgb([0, 0, 255])));
+        assert_eq!(grid[1][0].bg, None);
+        assert_eq!(
+            encode(&grid),
+            "\x1b[38;2;255;0;0;48;2;0;0;255m▀▀\x1b[0m\n\
+             \x1b[38;2;0;255;0m▀\x1b[38;2;0;0;0m▀\x1b[0m\n"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+///the viewer only ever shows one image, every frame replaces it
+pub const IMAGE_ID: u32 = 1;
+///base64 bytes per escape sequence, the protocol allows at most 4096
+const CHUNK_SIZE: usize = 4096;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn base64(bytes: &[u8]) -> String {
+    let mut string = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                string.push(BASE64_ALPHABET[(group >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                string.push('=');
+            }
+        }
+    }
+    string
+}
+
+///transmits the frame as 24 bit rgb and places it at the cursor
+pub fn encode(frame: &[Vec<[u8; 3]>], image_id: u32) -> String {
+    let height = frame.len();
+    let width = frame.iter().map(|row| row.len()).max().unwrap_or(0);
+    let mut rgb: Vec<u8> = Vec::with_capacity(width * height * 3);
+    for row in frame {
+        for x in 0..width {
+            rgb.extend_from_slice(row.get(x).unwrap_or(&[0, 0, 0]));
+        }
+    }
+    let payload = base64(&rgb);
+
+    //a=T transmits and displays, reusing i and p replaces the old image instead of stacking
+    //q=2 keeps the terminal from answering into our stdin
+    let keys = format!("a=T,f=24,s={},v={},i={},p=1,q=2", width, height, image_id);
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(CHUNK_SIZE).collect();
+    if chunks.is_empty() {
+        return format!("\x1b_G{};\x1b\\", keys);
+    }
+    let mut string = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        let chunk = std::str::from_utf8(chunk).unwrap_or("");
+        if i == 0 {
+            string += &format!("\x1b_G{},m={};{}\x1b\\", keys, more, chunk);
+        } else {
+            string += &format!("\x1b_Gm={};{}\x1b\\", more, chunk);
+        }
+    }
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+        assert_eq!(base64(&[0xff, 0xfe, 0x00]), "//4A");
+    }
+    #[test]
+    fn test_encode_kitty_small() {
+        let frame = vec![vec![[255, 0, 0], [0, 255, 0]]];
+        assert_eq!(
+            encode(&frame, 7),
+            "\x1b_Ga=T,f=24,s=2,v=1,i=7,p=1,q=2,m=0;/wAAAP8A\x1b\\"
+        );
+    }
+    #[test]
+    fn test_encode_kitty_chunks() {
+        //3072 bytes of rgb are exactly one chunk of base64
+        let frame = vec![vec![[1, 2, 3]; 1024]; 2];
+        let string = encode(&frame, IMAGE_ID);
+        assert_eq!(string.matches("\x1b_G").count(), 2);
+        assert!(string.starts_with("\x1b_Ga=T,f=24,s=1024,v=2,i=1,p=1,q=2,m=1;"));
+        assert!(string.contains("\x1b\\\x1b_Gm=0;"));
+        assert!(string.ends_with("\x1b\\"));
+    }
+}
@@ -0,0 +1,354 @@
+use crate::camera;
+use crate::terminal::{self, Size};
+
+pub mod ansi;
+pub mod ascii;
+pub mod braille;
+pub mod halfblock;
+pub mod kitty;
+pub mod palette;
+pub mod sixel;
+
+///how a frame reaches the terminal
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Backend {
+    ///kitty or sixel if the terminal supports it, colored cells otherwise, ascii where `TERM` has no colors;
+    ///resolved once at startup
+    Auto,
+    ///luminance ramp glyphs, no colors
+    Ascii,
+    ///background colors, one pixel per cell
+    Color,
+    ///two pixels per cell using '▀', square pixels at twice the vertical resolution
+    HalfBlock,
+    ///2x4 braille dots per cell, monochrome
+    Braille,
+    ///real pixels for terminals with sixel graphics
+    Sixel,
+    ///real pixels through the kitty graphics protocol
+    Kitty,
+}
+impl Backend {
+    ///replaces `Auto` by what the terminal can show
+    pub fn resolve(self) -> Backend {
+        match self {
+            Backend::Auto if std::env::var("TERM").is_ok_and(|term| term == "xterm-kitty") => {
+                Backend::Kitty
+            }
+            Backend::Auto if terminal::supports_sixel() => Backend::Sixel,
+            Backend::Auto => Backend::without_graphics(
+                std::env::var("COLORTERM").ok().as_deref(),
+                std::env::var("TERM").ok().as_deref(),
+            ),
+            backend => backend,
+        }
+    }
+    ///colored cells unless the terminal reports no colors at all,
+    ///`palette::ColorMode::detect` picks truecolor, 256 or 16 of them from the same variables
+    pub fn without_graphics(colorterm: Option<&str>, term: Option<&str>) -> Backend {
+        match (colorterm, term) {
+            (None | Some(""), None | Some("" | "dumb")) => Backend::Ascii,
+            _ => Backend::Color,
+        }
+    }
+    ///how many pixels fit into the terminal, one row is kept free so the last line break doesn't scroll
+    pub fn resolution(&self, size: Size) -> (usize, usize) {
+        let rows = size.rows.saturating_sub(1).max(1);
+        let (width, height) = match self {
+            Backend::Auto | Backend::Ascii | Backend::Color => (size.columns, rows),
+            Backend::HalfBlock => (size.columns, rows * 2),
+            Backend::Braille => (size.columns * 2, rows * 4),
+            Backend::Sixel | Backend::Kitty => {
+                if size.pixel_width > 0 && size.pixel_height > 0 {
+                    (size.pixel_width, size.pixel_height * rows / size.rows)
+                } else {
+                    //the terminal doesn't tell, assume the common 8x16 pixel cell
+                    (size.columns * 8, rows * 16)
+                }
+            }
+        };
+        (width.max(1), height.max(1))
+    }
+    ///width divided by height of one pixel on screen
+    pub fn pixel_aspect(&self) -> f64 {
+        match self {
+            Backend::Auto | Backend::Ascii | Backend::Color => camera::DEFAULT_PIXEL_ASPECT,
+            //half a cell high, or half a cell wide and a quarter high
+            Backend::HalfBlock | Backend::Braille => 2. * camera::DEFAULT_PIXEL_ASPECT,
+            Backend::Sixel | Backend::Kitty => 1.,
+        }
+    }
+}
+
+///everything that decides how a frame is drawn
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub backend: Backend,
+    pub ramp: ascii::Ramp,
+    pub dither: palette::Dither,
+    pub colors: palette::ColorMode,
+    ///luminance above which a braille dot is raised when not dithering
+    pub threshold: f64,
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Color {
+    Rgb([u8; 3]),
+    ///one of the 256 xterm colors
+    Indexed(u8),
+    ///one of the 16 basic colors, 8-15 are the bright ones
+    Basic(u8),
+}
+
+///one terminal cell, `None` keeps the terminal's default color
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Cell {
+    pub glyph: char,
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+impl Cell {
+    pub fn plain(glyph: char) -> Cell {
+        Cell {
+            glyph,
+            fg: None,
+            bg: None,
+        }
+    }
+}
+
+///relative luminance between 0 and 1 (rec. 709 weights)
+pub fn luminance(color: [u8; 3]) -> f64 {
+    (0.2126 * color[0] as f64 + 0.7152 * color[1] as f64 + 0.0722 * color[2] as f64) / 255.
+}
+
+///turns a frame from `camera::raycasting` into text for the terminal
+pub fn render(frame: &[Vec<[u8; 3]>], settings: &Settings) -> String {
+    match cells(frame, settings) {
+        Some(grid) => encode(&grid),
+        None if settings.backend == Backend::Kitty => kitty::encode(frame, kitty::IMAGE_ID),
+        None => sixel::encode(frame, settings.dither),
+    }
+}
+
+///the cells of a frame, `None` for backends that draw real pixels instead of glyphs
+pub fn cells(frame: &[Vec<[u8; 3]>], settings: &Settings) -> Option<Vec<Vec<Cell>>> {
+    match settings.backend {
+        Backend::Auto | Backend::Ascii => Some(ascii::render(frame, &settings.ramp)),
+        Backend::Color => Some(ansi::render(frame, settings.colors, settings.dither)),
+        Backend::HalfBlock => Some(halfblock::render(frame, settings.colors, settings.dither)),
+        Backend::Braille => Some(braille::render(frame, settings.dither, settings.threshold)),
+        Backend::Sixel | Backend::Kitty => None,
+    }
+}
+
+fn push_color(params: &mut Vec<String>, color: Color, background: bool) {
+    match color {
+        Color::Rgb([r, g, b]) => params.push(format!(
+            "{};2;{};{};{}",
+            if background { 48 } else { 38 },
+            r,
+            g,
+            b
+        )),
+        Color::Indexed(index) => {
+            params.push(format!("{};5;{}", if background { 48 } else { 38 }, index))
+        }
+        Color::Basic(index) => {
+            let base = match (background, index < 8) {
+                (false, true) => 30,
+                (false, false) => 90,
+                (true, true) => 40,
+                (true, false) => 100,
+            };
+            params.push((base + index % 8).to_string())
+        }
+    }
+}
+
+///turns rows of cells into text, escape sequences are only written where the colors change
+pub fn encode(grid: &[Vec<Cell>]) -> String {
+    let mut string = String::new();
+    for row in grid {
+        string += &encode_cells(row);
+        string.push('\n');
+    }
+    string
+}
+
+///only the cells that differ from `previous`, each run of them behind a cursor move
+pub fn encode_changes(previous: &[Vec<Cell>], grid: &[Vec<Cell>]) -> String {
+    let mut string = String::new();
+    for (y, row) in grid.iter().enumerate() {
+        let unchanged = |x: usize| previous.get(y).and_then(|old| old.get(x)) == Some(&row[x]);
+        let mut x = 0;
+        while x < row.len() {
+            if unchanged(x) {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < row.len() && !unchanged(x) {
+                x += 1;
+            }
+            string += &format!("\x1b[{};{}H", y + 1, start + 1);
+            string += &encode_cells(&row[start..x]);
+        }
+    }
+    string
+}
+
+///one stretch of cells starting and ending with the terminal's default colors
+fn encode_cells(cells: &[Cell]) -> String {
+    let mut string = String::new();
+    let mut fg: Option<Color> = None;
+    let mut bg: Option<Color> = None;
+    for cell in cells {
+        if cell.fg != fg || cell.bg != bg {
+            let mut params: Vec<String> = vec![];
+            //a color can only be taken back by resetting both
+            let reset = (fg.is_some() && cell.fg.is_none()) || (bg.is_some() && cell.bg.is_none());
+            if reset {
+                params.push("0".to_owned());
+            }
+            if let Some(color) = cell.fg {
+                if reset || cell.fg != fg {
+                    push_color(&mut params, color, false);
+                }
+            }
+            if let Some(color) = cell.bg {
+                if reset || cell.bg != bg {
+                    push_color(&mut params, color, true);
+                }
+            }
+            string += &format!("\x1b[{}m", params.join(";"));
+            fg = cell.fg;
+            bg = cell.bg;
+        }
+        string.push(cell.glyph);
+    }
+    //reset at the end, otherwise the background bleeds into the next line
+    if fg.is_some() || bg.is_some() {
+        string += "\x1b[0m";
+    }
+    string
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_luminance_1() {
+        assert_eq!(luminance([0, 0, 0]), 0.);
+        assert!((luminance([255, 255, 255]) - 1.).abs() < 1e-12);
+        assert!(luminance([0, 255, 0]) > luminance([255, 0, 0]));
+        assert!(luminance([255, 0, 0]) > luminance([0, 0, 255]));
+    }
+    #[test]
+    fn test_resolution_1() {
+        let size = Size {
+            columns: 120,
+            rows: 41,
+            pixel_width: 960,
+            pixel_height: 656,
+        };
+        assert_eq!(Backend::Ascii.resolution(size), (120, 40));
+        assert_eq!(Backend::HalfBlock.resolution(size), (120, 80));
+        assert_eq!(Backend::Braille.resolution(size), (240, 160));
+        assert_eq!(Backend::Sixel.resolution(size), (960, 640));
+        let no_pixels = Size {
+            pixel_width: 0,
+            pixel_height: 0,
+            ..size
+        };
+        assert_eq!(Backend::Kitty.resolution(no_pixels), (960, 640));
+        let tiny = Size {
+            columns: 1,
+            rows: 1,
+            pixel_width: 0,
+            pixel_height: 0,
+        };
+        assert_eq!(Backend::Color.resolution(tiny), (1, 1));
+    }
+    #[test]
+    fn test_without_graphics() {
+        use palette::ColorMode;
+        for (colorterm, term, colors) in [
+            (
+                Some("truecolor"),
+                Some("xterm-256color"),
+                ColorMode::TrueColor,
+            ),
+            (None, Some("xterm-256color"), ColorMode::Palette256),
+            (None, Some("linux"), ColorMode::Palette16),
+        ] {
+            assert_eq!(Backend::without_graphics(colorterm, term), Backend::Color);
+            assert_eq!(ColorMode::from_env(colorterm, term), colors);
+        }
+        assert_eq!(
+            Backend::without_graphics(None, Some("dumb")),
+            Backend::Ascii
+        );
+        assert_eq!(Backend::without_graphics(Some(""), None), Backend::Ascii);
+    }
+    #[test]
+    fn test_encode_plain() {
+        let grid = vec![
+            vec![Cell::plain('a'), Cell::plain('b')],
+            vec![Cell::plain('c')],
+        ];
+        assert_eq!(encode(&grid), "ab\nc\n");
+    }
+    #[test]
+    fn test_encode_only_on_change() {
+        let red = Some(Color::Rgb([255, 0, 0]));
+        let blue = Some(Color::Rgb([0, 0, 255]));
+        let cell = |fg, bg| Cell { glyph: 'x', fg, bg };
+        let grid = vec![vec![
+            cell(None, red),
+            cell(None, red),
+            cell(blue, red),
+            cell(blue, None),
+            cell(None, None),
+        ]];
+        assert_eq!(
+            encode(&grid),
+            "\x1b[48;2;255;0;0mxx\x1b[38;2;0;0;255mx\x1b[0;38;2;0;0;255mx\x1b[0mx\n"
+        );
+        let palettes = vec![vec![
+            cell(Some(Color::Indexed(196)), Some(Color::Basic(4))),
+            cell(Some(Color::Basic(9)), Some(Color::Basic(12))),
+        ]];
+        assert_eq!(
+            encode(&palettes),
+            "\x1b[38;5;196;44mx\x1b[91;104mx\x1b[0m\n"
+        );
+        let trailing = vec![vec![cell(None, red)], vec![cell(None, red)]];
+        assert_eq!(
+            encode(&trailing),
+            "\x1b[48;2;255;0;0mx\x1b[0m\n\x1b[48;2;255;0;0mx\x1b[0m\n"
+        );
+    }
+    #[test]
+    fn test_encode_changes() {
+        let red = Some(Color::Rgb([255, 0, 0]));
+        let previous = vec![
+            vec![Cell::plain('a'), Cell::plain('b'), Cell::plain('c')],
+            vec![Cell::plain('d'), Cell::plain('e'), Cell::plain('f')],
+        ];
+        assert_eq!(encode_changes(&previous, &previous), "");
+        let mut grid = previous.clone();
+        grid[0][1].glyph = 'x';
+        grid[0][2].glyph = 'y';
+        grid[1][0].bg = red;
+        assert_eq!(
+            encode_changes(&previous, &grid),
+            "\x1b[1;2Hxy\x1b[2;1H\x1b[48;2;255;0;0md\x1b[0m"
+        );
+        //rows and cells the previous frame didn't have count as changed
+        assert_eq!(
+            encode_changes(&previous[..1], &grid),
+            "\x1b[1;2Hxy\x1b[2;1H\x1b[48;2;255;0;0md\x1b[0mef"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+///how many colors the terminal can show
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum ColorMode {
+    TrueColor,
+    Palette256,
+    Palette16,
+}
+impl ColorMode {
+    ///reads `COLORTERM` and `TERM` from the environment
+    pub fn detect() -> ColorMode {
+        ColorMode::from_env(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+    pub fn from_env(colorterm: Option<&str>, term: Option<&str>) -> ColorMode {
+        if let Some("truecolor" | "24bit") = colorterm {
+            return ColorMode::TrueColor;
+        }
+        match term {
+            Some(term) if term.contains("direct") => ColorMode::TrueColor,
+            Some(term) if term.contains("256color") => ColorMode::Palette256,
+            _ => ColorMode::Palette16,
+        }
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Dither {
+    None,
+    ///4x4 bayer matrix, stable between frames
+    Ordered,
+    ///error diffusion, smoother but patterns move when the image moves
+    FloydSteinberg,
+}
+
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+///the 16 basic colors as xterm draws them by default
+pub const ANSI_16: [[u8; 3]; 16] = [
+    [0, 0, 0],
+    [205, 0, 0],
+    [0, 205, 0],
+    [205, 205, 0],
+    [0, 0, 238],
+    [205, 0, 205],
+    [0, 205, 205],
+    [229, 229, 229],
+    [127, 127, 127],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [92, 92, 255],
+    [255, 0, 255],
+    [0, 255, 255],
+    [255, 255, 255],
+];
+
+///all 256 xterm colors: the basic 16, a 6x6x6 color cube and 24 grays
+pub fn xterm_256() -> Vec<[u8; 3]> {
+    let mut palette = ANSI_16.to_vec();
+    let levels = [0, 95, 135, 175, 215, 255];
+    for r in levels {
+        for g in levels {
+            for b in levels {
+                palette.push([r, g, b]);
+            }
+        }
+    }
+    for gray in 0..24 {
+        let value = 8 + gray * 10;
+        palette.push([value, value, value]);
+    }
+    palette
+}
+
+///index of the palette entry closest to `color`
+pub fn nearest(palette: &[[u8; 3]], color: [f64; 3]) -> usize {
+    let mut best = 0;
+    let mut best_distance = f64::MAX;
+    for (index, entry) in palette.iter().enumerate() {
+        let distance: f64 = (0..3)
+            .map(|channel| {
+                let difference = entry[channel] as f64 - color[channel];
+                difference * difference
+            })
+            .sum();
+        if distance < best_distance {
+            best = index;
+            best_distance = distance;
+        }
+    }
+    best
+}
+
+///maps every pixel onto a palette index
+pub fn quantize(frame: &[Vec<[u8; 3]>], palette: &[[u8; 3]], dither: Dither) -> Vec<Vec<usize>> {
+    match dither {
+        Dither::None => frame
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|pixel| nearest(palette, pixel.map(|channel| channel as f64)))
+                    .collect()
+            })
+            .collect(),
+        Dither::Ordered => {
+            //roughly the distance between two neighbouring palette colors
+            let spread = 256. / (palette.len() as f64).cbrt();
+            frame
+                .iter()
+                .enumerate()
+                .map(|(y, row)| {
+                    row.iter()
+                        .enumerate()
+                        .map(|(x, pixel)| {
+                            let threshold = (BAYER_4X4[y % 4][x % 4] as f64 + 0.5) / 16. - 0.5;
+                            let color = pixel.map(|channel| channel as f64 + threshold * spread);
+                            nearest(palette, color)
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+        Dither::FloydSteinberg => {
+            let mut errors: Vec<Vec<[f64; 3]>> =
+                frame.iter().map(|row| vec![[0.; 3]; row.len()]).collect();
+            let mut indices: Vec<Vec<usize>> = vec![];
+            for y in 0..frame.len() {
+                let mut row_indices: Vec<usize> = vec![];
+                for x in 0..frame[y].len() {
+                    let mut color = [0.; 3];
+                    for channel in 0..3 {
+                        color[channel] =
+                            (frame[y][x][channel] as f64 + errors[y][x][channel]).clamp(0., 255.);
+                    }
+                    let index = nearest(palette, color);
+                    row_indices.push(index);
+
+                    let mut spread_error = |y: usize, x: usize, weight: f64| {
+                        if y < errors.len() && x < errors[y].len() {
+                            for channel in 0..3 {
+                                errors[y][x][channel] +=
+                                    (color[channel] - palette[index][channel] as f64) * weight;
+                            }
+                        }
+                    };
+                    spread_error(y, x + 1, 7. / 16.);
+                    if x > 0 {
+                        spread_error(y + 1, x - 1, 3. / 16.);
+                    }
+                    spread_error(y + 1, x, 5. / 16.);
+                    spread_error(y + 1, x + 1, 1. / 16.);
+                }
+                indices.push(row_indices);
+            }
+            indices
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_color_mode_from_env() {
+        assert_eq!(
+            ColorMode::from_env(Some("truecolor"), Some("xterm-256color")),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            ColorMode::from_env(Some("24bit"), None),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            ColorMode::from_env(None, Some("xterm-256color")),
+            ColorMode::Palette256
+        );
+        assert_eq!(
+            ColorMode::from_env(None, Some("xterm-direct")),
+            ColorMode::TrueColor
+        );
+        assert_eq!(
+            ColorMode::from_env(None, Some("linux")),
+            ColorMode::Palette16
+        );
+        assert_eq!(ColorMode::from_env(None, None), ColorMode::Palette16);
+    }
+    #[test]
+    fn test_xterm_256() {
+        let palette = xterm_256();
+        assert_eq!(palette.len(), 256);
+        assert_eq!(palette[16], [0, 0, 0]);
+        assert_eq!(palette[196], [255, 0, 0]);
+        assert_eq!(palette[231], [255, 255, 255]);
+        assert_eq!(palette[232], [8, 8, 8]);
+        assert_eq!(palette[255], [238, 238, 238]);
+    }
+    #[test]
+    fn test_quantize_without_dither() {
+        let frame = vec![vec![[250, 5, 5], [0, 0, 0], [250, 250, 250]]];
+        assert_eq!(
+            quantize(&frame, &ANSI_16, Dither::None),
+            vec![vec![9, 0, 15]]
+        );
+    }
+    #[test]
+    fn test_dithering_keeps_average_brightness() {
+        //a flat gray between black and white has to turn into a mix of both
+        let palette = [[0, 0, 0], [255, 255, 255]];
+        let frame = vec![vec![[128, 128, 128]; 8]; 8];
+        for dither in [Dither::Ordered, Dither::FloydSteinberg] {
+            let indices = quantize(&frame, &palette, dither);
+            let white: usize = indices.iter().flatten().sum();
+            assert!((28..=36).contains(&white), "{:?}: {} white", dither, white);
+        }
+        let flat: usize = quantize(&frame, &palette, Dither::None)
+            .iter()
+            .flatten()
+            .sum();
+        assert_eq!(flat, 64);
+    }
+    #[test]
+    fn test_dithering_keeps_exact_colors() {
+        let frame = vec![vec![[255, 0, 0], [0, 0, 0]]; 4];
+        for dither in [Dither::None, Dither::FloydSteinberg] {
+            assert_eq!(quantize(&frame, &ANSI_16, dither), vec![vec![9, 0]; 4]);
+        }
+    }
+}
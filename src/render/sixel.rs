@@ -0,0 +1,101 @@
+use super::palette::{self, Dither};
+
+///sixel data starts with DCS, `q` selects sixel mode
+const START: &str = "\x1bPq";
+const END: &str = "\x1b\\";
+
+///encodes the frame as a sixel image with up to 256 colors
+pub fn encode(frame: &[Vec<[u8; 3]>], dither: Dither) -> String {
+    let palette = palette::xterm_256();
+    let indices = palette::quantize(frame, &palette, dither);
+    let height = indices.len();
+    let width = indices.iter().map(|row| row.len()).max().unwrap_or(0);
+
+    let mut string = String::from(START);
+    //pixel aspect 1:1 and the image size
+    string += &format!("\"1;1;{};{}", width, height);
+
+    let mut used = [false; 256];
+    for index in indices.iter().flatten() {
+        used[*index] = true;
+    }
+    for (index, color) in palette.iter().enumerate() {
+        if used[index] {
+            //sixel colors are given in percent
+            let percent = color.map(|channel| (channel as u32 * 100 + 127) / 255);
+            string += &format!("#{};2;{};{};{}", index, percent[0], percent[1], percent[2]);
+        }
+    }
+
+    //every band covers six rows, one character per column and color
+    for band in indices.chunks(6) {
+        let mut colors_in_band: Vec<usize> = band.iter().flatten().copied().collect();
+        colors_in_band.sort_unstable();
+        colors_in_band.dedup();
+        for (i, color) in colors_in_band.iter().enumerate() {
+            if i > 0 {
+                //back to the start of the band for the next color
+                string.push('$');
+            }
+            string += &format!("#{}", color);
+            let mut sixels: Vec<u8> = vec![];
+            for x in 0..width {
+                let mut bits = 0u8;
+                for (dy, row) in band.iter().enumerate() {
+                    if row.get(x) == Some(color) {
+                        bits |= 1 << dy;
+                    }
+                }
+                sixels.push(bits);
+            }
+            push_run_length(&mut string, &sixels);
+        }
+        string.push('-');
+    }
+    string += END;
+    string
+}
+
+///writes sixels, runs of more than three are shortened to `!<count><sixel>`
+fn push_run_length(string: &mut String, sixels: &[u8]) {
+    let mut i = 0;
+    while i < sixels.len() {
+        let mut run = 1;
+        while i + run < sixels.len() && sixels[i + run] == sixels[i] {
+            run += 1;
+        }
+        let character = (sixels[i] + 63) as char;
+        if run > 3 {
+            *string += &format!("!{}{}", run, character);
+        } else {
+            for _ in 0..run {
+                string.push(character);
+            }
+        }
+        i += run;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_push_run_length() {
+        let mut string = String::new();
+        push_run_length(&mut string, &[0, 0, 0, 1, 1, 1, 1, 1, 63]);
+        assert_eq!(string, "???!5@~");
+    }
+    #[test]
+    fn test_encode_sixel_1() {
+        let red = [255, 0, 0];
+        let black = [0, 0, 0];
+        //7 rows: a full band and one row of a second band
+        let mut frame = vec![vec![red, red, black, black, black, black]; 6];
+        frame.push(vec![black; 6]);
+        assert_eq!(
+            encode(&frame, Dither::None),
+            "\x1bPq\"1;1;6;7#0;2;0;0;0#9;2;100;0;0\
+             #0??!4~$#9~~!4?-#0!6@-\x1b\\"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use crate::geometry::Vector3;
+
+///a light infinitely far away, every ray of it travels along `direction`
+#[derive(Copy, Clone, Debug)]
+pub struct Light {
+    pub direction: Vector3,
+    pub intensity: f64,
+}
+impl std::fmt::Display for Light {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "Light:\n        Direction: {}\n        Intensity: {}\n\n",
+            self.direction, self.intensity
+        )
+    }
+}
+
+///how the brightness varies across a polygon
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Shading {
+    ///one brightness per polygon, edges stay visible
+    Flat,
+    ///lights the corners and blends their brightness
+    Gouraud,
+    ///blends the corners' normals and lights every point, keeps highlights round
+    Phong,
+}
+
+///how a surface answers to light
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Material {
+    ///color the surface scatters evenly, tints the vertex colors
+    pub diffuse: [u8; 3],
+    ///color of the highlights
+    pub specular: [u8; 3],
+    ///the higher, the smaller and sharper the highlights
+    pub shininess: f64,
+    ///brightness (0-1) the surface keeps where no light reaches it
+    pub ambient: f64,
+}
+impl std::fmt::Display for Material {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "Material:\n        Diffuse: {:?}\n        Specular: {:?}\n        Shininess: {}\n        Ambient: {}\n\n",
+            self.diffuse, self.specular, self.shininess, self.ambient
+        )
+    }
+}
+
+///creates a white, matte material
+pub fn new_material() -> Material {
+    Material {
+        diffuse: [255, 255, 255],
+        specular: [0, 0, 0],
+        shininess: 32.,
+        ambient: 0.,
+    }
+}
+
+///creates a light shining from the upper left of the camera into the scene
+pub fn new_light() -> Light {
+    Light {
+        direction: Vector3 {
+            x: -0.5,
+            y: -0.5,
+            z: 1.,
+        },
+        intensity: 1.,
+    }
+}
+
+///brightness between 0 and `light.intensity` of a face with the given normal
+pub fn lambert(light: &Light, normal: Vector3) -> f64 {
+    let to_light = -light.direction;
+    let lengths = to_light.length() * normal.length();
+    if lengths == 0. {
+        return 0.;
+    }
+    let cos_angle = normal.dot(to_light) / lengths;
+    light.intensity * cos_angle.max(0.)
+}
+
+///blinn-phong: the surface color lit by the ambient term and lambert, plus a highlight where the normal
+///points halfway between the light and the viewer
+pub fn blinn_phong(
+    light: &Light,
+    material: &Material,
+    color: [u8; 3],
+    normal: Vector3,
+    to_viewer: Vector3,
+) -> [u8; 3] {
+    let surface = [0, 1, 2].map(|channel| {
+        (color[channel] as f64 * material.diffuse[channel] as f64 / 255.).round() as u8
+    });
+    let diffuse = lambert(light, normal);
+    let lit = shade(surface, material.ambient + diffuse);
+    //no highlight on the side facing away from the light
+    if diffuse <= 0. {
+        return lit;
+    }
+    let halfway = ((-light.direction).normalize() + to_viewer.normalize()).normalize();
+    let highlight = light.intensity
+        * normal
+            .normalize()
+            .dot(halfway)
+            .max(0.)
+            .powf(material.shininess);
+    let gloss = shade(material.specular, highlight);
+    [0, 1, 2].map(|channel| lit[channel].saturating_add(gloss[channel]))
+}
+
+///scales a color by a brightness, brightness above 1 saturates at white
+pub fn shade(color: [u8; 3], brightness: f64) -> [u8; 3] {
+    color.map(|channel| (channel as f64 * brightness).round().clamp(0., 255.) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_lambert_1() {
+        let light = Light {
+            direction: Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 2.,
+            },
+            intensity: 0.8,
+        };
+        let facing = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: -5.,
+        };
+        let tilted = Vector3 {
+            x: 1.,
+            y: 0.,
+            z: -1.,
+        };
+        let away = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: 1.,
+        };
+        assert!((lambert(&light, facing) - 0.8).abs() < 1e-12);
+        assert!((lambert(&light, tilted) - 0.8 * f64::sqrt(0.5)).abs() < 1e-12);
+        assert_eq!(lambert(&light, away), 0.);
+    }
+    #[test]
+    fn test_shade_1() {
+        assert_eq!(shade([255, 255, 255], 0.5), [128, 128, 128]);
+        assert_eq!(shade([200, 100, 0], 2.), [255, 200, 0]);
+        assert_eq!(shade([200, 100, 0], 0.), [0, 0, 0]);
+    }
+    #[test]
+    fn test_blinn_phong() {
+        let light = Light {
+            direction: Vector3 {
+                x: 0.,
+                y: 0.,
+                z: 1.,
+            },
+            intensity: 1.,
+        };
+        let matte = Material {
+            diffuse: [255, 128, 0],
+            specular: [0, 0, 0],
+            shininess: 32.,
+            ambient: 0.25,
+        };
+        let facing = Vector3 {
+            x: 0.,
+            y: 0.,
+            z: -1.,
+        };
+        let away = -facing;
+        let tilted = Vector3 {
+            x: 1.,
+            y: 0.,
+            z: -1.,
+        };
+        //without specular it is lambert plus ambient on the tinted surface
+        assert_eq!(
+            blinn_phong(&light, &matte, [255, 255, 255], facing, facing),
+            [255, 160, 0]
+        );
+        assert_eq!(
+            blinn_phong(&light, &matte, [200, 200, 200], away, facing),
+            [50, 25, 0]
+        );
+        let glossy = Material {
+            specular: [100, 100, 100],
+            ..matte
+        };
+        let dark = [0, 0, 0];
+        //the highlight is brightest where the normal halves light and view, and fades quickly off it
+        assert_eq!(blinn_phong(&light, &glossy, dark, facing, facing), [100; 3]);
+        let mirrored = Vector3 {
+            x: -1.,
+            y: 0.,
+            z: -1.,
+        };
+        assert_eq!(blinn_phong(&light, &glossy, dark, tilted, facing), [0; 3]);
+        //seen from the side, the highlight moves to the normal halfway between light and viewer
+        let halfway = (mirrored.normalize() + facing).normalize();
+        assert_eq!(
+            blinn_phong(&light, &glossy, dark, halfway, mirrored),
+            [100; 3]
+        );
+        //no highlight on the unlit side
+        assert_eq!(blinn_phong(&light, &glossy, dark, away, away), [0; 3]);
+    }
+}
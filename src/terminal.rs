@@ -0,0 +1,183 @@
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+///set by the SIGWINCH handler, cleared by `take_resized`
+static RESIZED: AtomicBool = AtomicBool::new(false);
+///set by the SIGINT handler
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+///size of the terminal in cells, and in pixels if the terminal reports them (0 otherwise)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Size {
+    pub columns: usize,
+    pub rows: usize,
+    pub pixel_width: usize,
+    pub pixel_height: usize,
+}
+impl std::fmt::Display for Size {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        write!(
+            f,
+            "{}x{} cells, {}x{} pixels",
+            self.columns, self.rows, self.pixel_width, self.pixel_height
+        )
+    }
+}
+
+///puts stdin into non-canonical mode without echo until dropped
+struct RawMode {
+    original: libc::termios,
+}
+impl RawMode {
+    ///reads return after `timeout_tenths` tenths of a second even if nothing arrived
+    fn enable(timeout_tenths: u8) -> Option<RawMode> {
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return None;
+        }
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = timeout_tenths;
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+            return None;
+        }
+        Some(RawMode { original })
+    }
+}
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original);
+        }
+    }
+}
+
+///asks the terminal on stdout for its size (TIOCGWINSZ)
+pub fn size() -> Option<Size> {
+    let mut window: libc::winsize = unsafe { std::mem::zeroed() };
+    if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut window) } != 0 {
+        return None;
+    }
+    if window.ws_col == 0 || window.ws_row == 0 {
+        return None;
+    }
+    Some(Size {
+        columns: window.ws_col as usize,
+        rows: window.ws_row as usize,
+        pixel_width: window.ws_xpixel as usize,
+        pixel_height: window.ws_ypixel as usize,
+    })
+}
+
+extern "C" fn on_resize(_signal: libc::c_int) {
+    RESIZED.store(true, Ordering::SeqCst);
+}
+
+///installs a SIGWINCH handler, afterwards `take_resized` reports window size changes
+pub fn watch_resize() {
+    let handler: extern "C" fn(libc::c_int) = on_resize;
+    unsafe {
+        libc::signal(libc::SIGWINCH, handler as libc::sighandler_t);
+    }
+}
+
+///true once after every resize
+pub fn take_resized() -> bool {
+    RESIZED.swap(false, Ordering::SeqCst)
+}
+
+extern "C" fn on_interrupt(_signal: libc::c_int) {
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+///catches Ctrl-C so the main loop can leave and restore the terminal instead of dying
+pub fn watch_interrupt() {
+    let handler: extern "C" fn(libc::c_int) = on_interrupt;
+    unsafe {
+        libc::signal(libc::SIGINT, handler as libc::sighandler_t);
+    }
+}
+
+///true from the first Ctrl-C on
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+pub fn is_terminal() -> bool {
+    unsafe { libc::isatty(libc::STDIN_FILENO) == 1 && libc::isatty(libc::STDOUT_FILENO) == 1 }
+}
+
+///sends DA1 (`ESC [ c`) and returns the attributes the terminal answers with
+pub fn query_device_attributes() -> Option<Vec<u32>> {
+    if !is_terminal() {
+        return None;
+    }
+    let _raw_mode = RawMode::enable(2)?;
+    let mut stdout = std::io::stdout();
+    stdout.write_all(b"\x1b[c").ok()?;
+    stdout.flush().ok()?;
+
+    let mut response: Vec<u8> = vec![];
+    let mut buffer = [0u8; 64];
+    //terminals that don't know DA1 never answer, give up after a few empty reads
+    let mut empty_reads = 0;
+    while empty_reads < 3 && response.len() < 256 {
+        match std::io::stdin().read(&mut buffer) {
+            Ok(0) => empty_reads += 1,
+            Ok(count) => {
+                response.extend_from_slice(&buffer[..count]);
+                if let Some(attributes) = parse_device_attributes(&response) {
+                    return Some(attributes);
+                }
+            }
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+///parses a DA1 response like `ESC [ ? 62 ; 4 ; 22 c`
+pub fn parse_device_attributes(response: &[u8]) -> Option<Vec<u32>> {
+    let start = response.windows(3).position(|window| window == b"\x1b[?")? + 3;
+    let length = response[start..].iter().position(|byte| *byte == b'c')?;
+    let body = std::str::from_utf8(&response[start..start + length]).ok()?;
+    body.split(';').map(|number| number.parse().ok()).collect()
+}
+
+///attribute 4 in the DA1 response means sixel graphics
+pub fn supports_sixel() -> bool {
+    match query_device_attributes() {
+        Some(attributes) => attributes.contains(&4),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_take_resized() {
+        watch_resize();
+        take_resized();
+        unsafe {
+            libc::raise(libc::SIGWINCH);
+        }
+        assert!(take_resized());
+        assert!(!take_resized());
+    }
+    #[test]
+    fn test_parse_device_attributes() {
+        assert_eq!(
+            parse_device_attributes(b"\x1b[?62;4;6;22c"),
+            Some(vec![62, 4, 6, 22])
+        );
+        assert_eq!(
+            parse_device_attributes(b"noise\x1b[?1;2c"),
+            Some(vec![1, 2])
+        );
+        assert_eq!(parse_device_attributes(b"\x1b[?62;4"), None);
+        assert_eq!(parse_device_attributes(b"\x1b[?62;x;4c"), None);
+        assert_eq!(parse_device_attributes(b""), None);
+    }
+}